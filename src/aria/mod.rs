@@ -0,0 +1,21 @@
+//! Accessibility (ARIA) tree utilities
+//!
+//! Computes roles, accessible names and states of elements and renders them as a YAML-like text that
+//! can be used for snapshots that are much more robust to markup changes than raw HTML:
+//!
+//! ```text
+//! - heading "Todos" [level=1]
+//! - list:
+//!   - listitem:
+//!     - checkbox "Buy milk" [checked]
+//! - button "Add"
+//! ```
+
+mod name;
+mod role;
+pub(crate) mod template;
+mod tree;
+
+pub use name::accessible_name;
+pub use role::role;
+pub use tree::{AriaNode, aria_tree, aria_tree_of_element, render};
@@ -0,0 +1,97 @@
+use super::role::{NAME_FROM_CONTENT_ROLES, role};
+
+/// Computes the accessible name of an element.
+///
+/// This is a simplified version of the [accname](https://www.w3.org/TR/accname-1.2/) algorithm that
+/// covers the usual cases: `aria-labelledby`, `aria-label`, `<label>`s, `alt`, text content and `title`
+pub fn accessible_name(elem: &web_sys::Element) -> String {
+    if let Some(labelledby) = elem.get_attribute("aria-labelledby") {
+        let document = elem.owner_document().expect("element has no document");
+        let name = labelledby
+            .split_whitespace()
+            .filter_map(|id| document.get_element_by_id(id))
+            .map(|label| normalized_text(&label))
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !name.is_empty() {
+            return name;
+        }
+    }
+
+    if let Some(label) = elem
+        .get_attribute("aria-label")
+        .map(|label| label.trim().to_string())
+        .filter(|label| !label.is_empty())
+    {
+        return label;
+    }
+
+    let tag = elem.local_name();
+    if matches!(tag.as_str(), "input" | "select" | "textarea") {
+        let labels = labels(elem);
+        if !labels.is_empty() {
+            return labels.join(" ");
+        }
+        if tag == "input"
+            && matches!(
+                elem.get_attribute("type").as_deref(),
+                Some("button" | "submit" | "reset")
+            )
+        {
+            return elem.get_attribute("value").unwrap_or_default();
+        }
+    }
+
+    if tag == "img" {
+        if let Some(alt) = elem.get_attribute("alt") {
+            return alt;
+        }
+    }
+
+    if tag == "fieldset" {
+        if let Some(legend) = elem.query_selector(":scope > legend").unwrap() {
+            return normalized_text(&legend);
+        }
+    }
+
+    if role(elem).is_some_and(|role| NAME_FROM_CONTENT_ROLES.contains(&role.as_str())) {
+        let text = normalized_text(elem);
+        if !text.is_empty() {
+            return text;
+        }
+    }
+
+    elem.get_attribute("title").unwrap_or_default()
+}
+
+/// The text of the `<label>`s that reference this element, either by wrapping it or with `for`
+fn labels(elem: &web_sys::Element) -> Vec<String> {
+    let mut labels = Vec::new();
+
+    if let Some(wrapping) = elem.closest("label").unwrap() {
+        labels.push(normalized_text(&wrapping));
+    }
+
+    let id = elem.id();
+    if !id.is_empty() {
+        let document = elem.owner_document().expect("element has no document");
+        if let Some(label) = document
+            .query_selector(&format!("label[for=\"{id}\"]"))
+            .unwrap()
+        {
+            labels.push(normalized_text(&label));
+        }
+    }
+
+    labels.retain(|label| !label.is_empty());
+    labels
+}
+
+/// The text content of an element with its whitespace collapsed
+pub(crate) fn normalized_text(elem: &web_sys::Element) -> String {
+    normalize_whitespace(&elem.text_content().unwrap_or_default())
+}
+
+pub(crate) fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
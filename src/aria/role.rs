@@ -0,0 +1,118 @@
+use wasm_bindgen::JsCast as _;
+
+/// Roles that don't contribute anything to the accessibility tree, their children are
+/// hoisted to the parent
+const TRANSPARENT_ROLES: &[&str] = &["generic", "none", "presentation"];
+
+/// Roles whose accessible name can be computed from their content
+pub(crate) const NAME_FROM_CONTENT_ROLES: &[&str] = &[
+    "button",
+    "cell",
+    "checkbox",
+    "columnheader",
+    "gridcell",
+    "heading",
+    "link",
+    "menuitem",
+    "menuitemcheckbox",
+    "menuitemradio",
+    "option",
+    "radio",
+    "row",
+    "rowheader",
+    "switch",
+    "tab",
+    "tooltip",
+    "treeitem",
+];
+
+/// Computes the ARIA role of an element, either from its `role` attribute or the implicit role of
+/// its tag.
+///
+/// Returns `None` for elements that have no meaningful role (e.g. `div` or `span`)
+pub fn role(elem: &web_sys::Element) -> Option<String> {
+    if let Some(explicit) = elem
+        .get_attribute("role")
+        .and_then(|role| role.split_whitespace().next().map(ToString::to_string))
+    {
+        return (!TRANSPARENT_ROLES.contains(&explicit.as_str())).then_some(explicit);
+    }
+
+    implicit_role(elem).map(ToString::to_string)
+}
+
+fn implicit_role(elem: &web_sys::Element) -> Option<&'static str> {
+    let role = match elem.local_name().as_str() {
+        "a" | "area" if elem.has_attribute("href") => "link",
+        "article" => "article",
+        "aside" => "complementary",
+        "button" => "button",
+        "details" | "fieldset" | "optgroup" => "group",
+        "dialog" => "dialog",
+        "footer" => "contentinfo",
+        "form" => "form",
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => "heading",
+        "header" => "banner",
+        "hr" => "separator",
+        "img" if elem.get_attribute("alt").as_deref() == Some("") => return None,
+        "img" => "img",
+        "input" => return input_role(elem),
+        "li" => "listitem",
+        "main" => "main",
+        "nav" => "navigation",
+        "ol" | "ul" | "menu" => "list",
+        "option" => "option",
+        "p" => "paragraph",
+        "progress" => "progressbar",
+        "section" if elem.has_attribute("aria-label") || elem.has_attribute("aria-labelledby") => {
+            "region"
+        }
+        "select" => {
+            let select = elem.unchecked_ref::<web_sys::HtmlSelectElement>();
+            if select.multiple() || select.size() > 1 {
+                "listbox"
+            } else {
+                "combobox"
+            }
+        }
+        "table" => "table",
+        "tbody" | "thead" | "tfoot" => "rowgroup",
+        "td" => "cell",
+        "textarea" => "textbox",
+        "th" => "columnheader",
+        "tr" => "row",
+        _ => return None,
+    };
+
+    Some(role)
+}
+
+fn input_role(elem: &web_sys::Element) -> Option<&'static str> {
+    let role = match elem
+        .get_attribute("type")
+        .unwrap_or_default()
+        .to_lowercase()
+        .as_str()
+    {
+        "button" | "image" | "reset" | "submit" => "button",
+        "checkbox" => "checkbox",
+        "hidden" => return None,
+        "number" => "spinbutton",
+        "radio" => "radio",
+        "range" => "slider",
+        "search" => "searchbox",
+        _ => "textbox",
+    };
+
+    Some(role)
+}
+
+/// Whether this element and its subtree are excluded from the accessibility tree
+pub(crate) fn is_hidden(elem: &web_sys::Element) -> bool {
+    matches!(
+        elem.local_name().as_str(),
+        "script" | "style" | "template" | "noscript"
+    ) || elem.has_attribute("hidden")
+        || elem.get_attribute("aria-hidden").as_deref() == Some("true")
+        || (elem.local_name() == "input" && elem.get_attribute("type").as_deref() == Some("hidden"))
+}
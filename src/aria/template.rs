@@ -0,0 +1,262 @@
+use super::tree::{AriaNode, TEXT_ROLE};
+
+/// A node of an expected aria snapshot. Anything left out of it (names, states, children) matches anything
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct AriaTemplate {
+    role: String,
+    name: Option<String>,
+    states: Vec<(String, String)>,
+    children: Vec<AriaTemplate>,
+}
+
+/// Parses an aria snapshot as rendered by [`super::render`] into templates
+pub(crate) fn parse(snapshot: &str) -> Result<Vec<AriaTemplate>, String> {
+    let lines = snapshot
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| (line.len() - line.trim_start().len(), line.trim()))
+        .collect::<Vec<_>>();
+
+    let mut pos = 0;
+    let templates = parse_level(&lines, &mut pos, None)?;
+    if pos < lines.len() {
+        return Err(format!("unexpected indentation at line `{}`", lines[pos].1));
+    }
+    Ok(templates)
+}
+
+/// Parses all consecutive lines with the indentation of the first one
+fn parse_level(
+    lines: &[(usize, &str)],
+    pos: &mut usize,
+    parent_indent: Option<usize>,
+) -> Result<Vec<AriaTemplate>, String> {
+    let mut templates = Vec::new();
+    let Some(&(indent, _)) = lines.get(*pos) else {
+        return Ok(templates);
+    };
+    if parent_indent.is_some_and(|parent| indent <= parent) {
+        return Ok(templates);
+    }
+
+    while let Some(&(line_indent, line)) = lines.get(*pos) {
+        if line_indent != indent {
+            break;
+        }
+        *pos += 1;
+
+        let (mut template, has_children) = parse_line(line)?;
+        if has_children {
+            template.children = parse_level(lines, pos, Some(indent))?;
+        }
+        templates.push(template);
+    }
+
+    Ok(templates)
+}
+
+/// Parses a single line, also returning whether it ends with `:` so children come after it
+fn parse_line(line: &str) -> Result<(AriaTemplate, bool), String> {
+    let invalid = || format!("invalid aria snapshot line `{line}`");
+
+    let rest = line.strip_prefix("- ").ok_or_else(invalid)?;
+    let role_end = rest.find([' ', ':']).unwrap_or(rest.len());
+    let (role, mut rest) = (rest[..role_end].to_string(), rest[role_end..].trim_start());
+
+    let mut template = AriaTemplate {
+        role,
+        name: None,
+        states: Vec::new(),
+        children: Vec::new(),
+    };
+
+    if template.role == TEXT_ROLE {
+        let text = rest.strip_prefix(':').ok_or_else(invalid)?.trim();
+        template.name = Some(text.to_string());
+        return Ok((template, false));
+    }
+
+    if let Some(quoted) = rest.strip_prefix('"') {
+        let mut name = String::new();
+        let mut chars = quoted.char_indices();
+        let end = loop {
+            match chars.next().ok_or_else(invalid)? {
+                (_, '\\') => name.push(chars.next().ok_or_else(invalid)?.1),
+                (i, '"') => break i,
+                (_, c) => name.push(c),
+            }
+        };
+        template.name = Some(name);
+        rest = quoted[end + 1..].trim_start();
+    }
+
+    while let Some(state) = rest.strip_prefix('[') {
+        let end = state.find(']').ok_or_else(invalid)?;
+        let (name, value) = state[..end]
+            .split_once('=')
+            .unwrap_or((&state[..end], "true"));
+        template
+            .states
+            .push((name.trim().to_string(), value.trim().to_string()));
+        rest = state[end + 1..].trim_start();
+    }
+
+    match rest.strip_prefix(':').map(str::trim) {
+        None if rest.is_empty() => Ok((template, false)),
+        None => Err(invalid()),
+        Some("") => Ok((template, true)),
+        Some(text) => {
+            template.children.push(AriaTemplate {
+                role: String::from(TEXT_ROLE),
+                name: Some(text.to_string()),
+                states: Vec::new(),
+                children: Vec::new(),
+            });
+            Ok((template, false))
+        }
+    }
+}
+
+/// Whether the templates match some list of siblings anywhere in the tree
+pub(crate) fn matches_anywhere(templates: &[AriaTemplate], nodes: &[AriaNode]) -> bool {
+    matches_siblings(templates, nodes)
+        || nodes
+            .iter()
+            .any(|node| matches_anywhere(templates, &node.children))
+}
+
+/// Whether every template matches some node, in order (though not every node needs to be matched)
+fn matches_siblings(templates: &[AriaTemplate], nodes: &[AriaNode]) -> bool {
+    let mut nodes = nodes.iter();
+    templates
+        .iter()
+        .all(|template| nodes.any(|node| matches_node(template, node)))
+}
+
+fn matches_node(template: &AriaTemplate, node: &AriaNode) -> bool {
+    if template.role != node.role
+        || template
+            .name
+            .as_ref()
+            .is_some_and(|name| *name != node.name)
+        || !template
+            .states
+            .iter()
+            .all(|state| node.states.contains(state))
+    {
+        return false;
+    }
+
+    // text that equals the name is omitted from the tree, so it has to be compared with the name
+    if node.children.is_empty()
+        && !template.children.is_empty()
+        && template
+            .children
+            .iter()
+            .all(|child| child.role == TEXT_ROLE)
+    {
+        return template
+            .children
+            .iter()
+            .filter_map(|child| child.name.as_deref())
+            .collect::<Vec<_>>()
+            .join(" ")
+            == node.name;
+    }
+
+    matches_siblings(&template.children, &node.children)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(role: &str, name: &str, children: Vec<AriaNode>) -> AriaNode {
+        AriaNode {
+            role: role.to_string(),
+            name: name.to_string(),
+            states: Vec::new(),
+            children,
+        }
+    }
+
+    #[test]
+    fn parses_lines() {
+        let templates = parse(
+            r#"
+            - heading "Some \"title\"" [level=1]
+            - list:
+              - listitem: One
+            - checkbox [checked]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            templates,
+            vec![
+                AriaTemplate {
+                    role: String::from("heading"),
+                    name: Some(String::from("Some \"title\"")),
+                    states: vec![(String::from("level"), String::from("1"))],
+                    children: vec![],
+                },
+                AriaTemplate {
+                    role: String::from("list"),
+                    name: None,
+                    states: vec![],
+                    children: vec![AriaTemplate {
+                        role: String::from("listitem"),
+                        name: None,
+                        states: vec![],
+                        children: vec![AriaTemplate {
+                            role: String::from(TEXT_ROLE),
+                            name: Some(String::from("One")),
+                            states: vec![],
+                            children: vec![],
+                        }],
+                    }],
+                },
+                AriaTemplate {
+                    role: String::from("checkbox"),
+                    name: None,
+                    states: vec![(String::from("checked"), String::from("true"))],
+                    children: vec![],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_lines() {
+        assert!(parse("heading").is_err());
+        assert!(parse("- heading \"unterminated").is_err());
+    }
+
+    #[test]
+    fn matches_partially() {
+        let tree = vec![node(
+            "main",
+            "",
+            vec![
+                node("heading", "Title", vec![]),
+                node(
+                    "list",
+                    "",
+                    vec![
+                        node("listitem", "", vec![node(TEXT_ROLE, "One", vec![])]),
+                        node("listitem", "", vec![node(TEXT_ROLE, "Two", vec![])]),
+                    ],
+                ),
+            ],
+        )];
+
+        let matches = |snapshot| matches_anywhere(&parse(snapshot).unwrap(), &tree);
+
+        assert!(matches("- list:\n  - listitem: Two"));
+        assert!(matches("- heading \"Title\"\n- list"));
+        assert!(matches("- heading: Title"));
+        assert!(!matches("- list:\n  - listitem: Two\n  - listitem: One"));
+        assert!(!matches("- heading \"Other\""));
+    }
+}
@@ -0,0 +1,241 @@
+use wasm_bindgen::JsCast as _;
+
+use super::{
+    name::{accessible_name, normalize_whitespace},
+    role::{is_hidden, role},
+};
+
+/// The role used for text nodes in the accessibility tree
+pub(crate) const TEXT_ROLE: &str = "text";
+
+/// A node of the accessibility tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AriaNode {
+    pub role: String,
+    /// The accessible name, or the content for text nodes
+    pub name: String,
+    /// States such as `checked` or `level`, with their values
+    pub states: Vec<(String, String)>,
+    pub children: Vec<AriaNode>,
+}
+
+impl AriaNode {
+    fn text(text: String) -> Self {
+        Self {
+            role: String::from(TEXT_ROLE),
+            name: text,
+            states: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    pub(crate) fn is_text(&self) -> bool {
+        self.role == TEXT_ROLE
+    }
+}
+
+/// Builds the accessibility tree for the children of the given node.
+///
+/// Elements without a meaningful role (`div`, `span`...) are omitted and their children hoisted
+pub fn aria_tree(root: &web_sys::Node) -> Vec<AriaNode> {
+    let mut nodes = Vec::new();
+    push_children(root, &mut nodes);
+    nodes
+}
+
+/// Builds the accessibility tree for an element itself, it may be a list if the element has no role
+pub fn aria_tree_of_element(elem: &web_sys::Element) -> Vec<AriaNode> {
+    let mut nodes = Vec::new();
+    push_node(elem.as_ref(), &mut nodes);
+    nodes
+}
+
+fn push_children(node: &web_sys::Node, nodes: &mut Vec<AriaNode>) {
+    use crate::util::NodeListExt as _;
+
+    for child in node.child_nodes().into_iterator() {
+        push_node(&child, nodes);
+    }
+}
+
+fn push_node(node: &web_sys::Node, nodes: &mut Vec<AriaNode>) {
+    match node.node_type() {
+        web_sys::Node::TEXT_NODE => {
+            let text = normalize_whitespace(&node.text_content().unwrap_or_default());
+            if text.is_empty() {
+                return;
+            }
+            match nodes.last_mut() {
+                Some(prev) if prev.is_text() => {
+                    prev.name.push(' ');
+                    prev.name.push_str(&text);
+                }
+                _ => nodes.push(AriaNode::text(text)),
+            }
+        }
+        web_sys::Node::ELEMENT_NODE => {
+            let elem = node.unchecked_ref::<web_sys::Element>();
+            if is_hidden(elem) {
+                return;
+            }
+
+            let Some(role) = role(elem) else {
+                push_children(node, nodes);
+                return;
+            };
+
+            let name = accessible_name(elem);
+            let mut children = Vec::new();
+            push_children(node, &mut children);
+            if let Some(value) = textbox_value(elem, &role) {
+                children.push(AriaNode::text(value));
+            }
+            // the name already says everything the text does
+            if children.iter().all(AriaNode::is_text)
+                && children
+                    .iter()
+                    .map(|c| c.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+                    == name
+            {
+                children.clear();
+            }
+
+            nodes.push(AriaNode {
+                states: states(elem, &role),
+                role,
+                name,
+                children,
+            });
+        }
+        _ => {}
+    }
+}
+
+fn states(elem: &web_sys::Element, role: &str) -> Vec<(String, String)> {
+    let mut states = Vec::new();
+    let mut push = |name: &str, value: String| states.push((name.to_string(), value));
+
+    let tag = elem.local_name();
+    if matches!(role, "checkbox" | "radio" | "switch" | "menuitemcheckbox") {
+        if let Some(input) = elem.dyn_ref::<web_sys::HtmlInputElement>() {
+            if input.indeterminate() {
+                push("checked", String::from("mixed"));
+            } else if input.checked() {
+                push("checked", String::from("true"));
+            }
+        } else if let Some(checked) = elem
+            .get_attribute("aria-checked")
+            .filter(|checked| checked != "false")
+        {
+            push("checked", checked);
+        }
+    }
+
+    if elem.has_attribute("disabled")
+        || elem.get_attribute("aria-disabled").as_deref() == Some("true")
+    {
+        push("disabled", String::from("true"));
+    }
+
+    if elem.get_attribute("aria-expanded").as_deref() == Some("true")
+        || (tag == "details" && elem.has_attribute("open"))
+    {
+        push("expanded", String::from("true"));
+    }
+
+    if role == "heading" {
+        let level = elem
+            .get_attribute("aria-level")
+            .or_else(|| tag.strip_prefix('h').map(ToString::to_string))
+            .unwrap_or_else(|| String::from("2"));
+        push("level", level);
+    }
+
+    if let Some(pressed) = elem
+        .get_attribute("aria-pressed")
+        .filter(|pressed| pressed != "false")
+    {
+        push("pressed", pressed);
+    }
+
+    let selected = match elem.dyn_ref::<web_sys::HtmlOptionElement>() {
+        Some(option) => option.selected(),
+        None => elem.get_attribute("aria-selected").as_deref() == Some("true"),
+    };
+    if selected {
+        push("selected", String::from("true"));
+    }
+
+    states
+}
+
+/// The current value of text inputs, which is shown as their content
+fn textbox_value(elem: &web_sys::Element, role: &str) -> Option<String> {
+    if !matches!(role, "textbox" | "searchbox" | "spinbutton") {
+        return None;
+    }
+
+    let value = if let Some(input) = elem.dyn_ref::<web_sys::HtmlInputElement>() {
+        input.value()
+    } else if let Some(textarea) = elem.dyn_ref::<web_sys::HtmlTextAreaElement>() {
+        textarea.value()
+    } else {
+        return None;
+    };
+
+    Some(value).filter(|value| !value.is_empty())
+}
+
+/// Renders the accessibility tree as a YAML-like text, one node per line
+///
+/// ```text
+/// - heading "Title" [level=1]
+/// - list:
+///   - listitem: One
+/// - checkbox "Agree" [checked]
+/// ```
+pub fn render(nodes: &[AriaNode]) -> String {
+    let mut lines = Vec::new();
+    for node in nodes {
+        render_node(node, 0, &mut lines);
+    }
+    lines.join("\n")
+}
+
+fn render_node(node: &AriaNode, depth: usize, lines: &mut Vec<String>) {
+    let indent = "  ".repeat(depth);
+
+    if node.is_text() {
+        lines.push(format!("{indent}- text: {}", node.name));
+        return;
+    }
+
+    let mut line = format!("{indent}- {}", node.role);
+    if !node.name.is_empty() {
+        line.push_str(&format!(" {}", quote(&node.name)));
+    }
+    for (state, value) in &node.states {
+        if value == "true" {
+            line.push_str(&format!(" [{state}]"));
+        } else {
+            line.push_str(&format!(" [{state}={value}]"));
+        }
+    }
+
+    match node.children.as_slice() {
+        [] => lines.push(line),
+        [only] if only.is_text() => lines.push(format!("{line}: {}", only.name)),
+        children => {
+            lines.push(format!("{line}:"));
+            for child in children {
+                render_node(child, depth + 1, lines);
+            }
+        }
+    }
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
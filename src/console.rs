@@ -0,0 +1,403 @@
+//! Capturing of what's printed to the console, to fail tests on errors and warnings
+//!
+//! While a [`ConsoleCapture`] is alive, every call to `console.debug/log/info/warn/error` is
+//! recorded (and still printed), along with uncaught errors and unhandled promise rejections:
+//!
+//! ```ignore
+//! let console = ConsoleCapture::install().strict();
+//! let wrapper = mount_test(|| view! { <Counter /> });
+//! // ...
+//! // fails the test when dropped if anything warned, e.g. Leptos about a signal read outside of a
+//! // tracking context
+//! ```
+//!
+//! The console is patched once for every capture that's alive, so captures can overlap and be
+//! dropped in any order. Each one records everything printed while it's alive.
+//!
+//! A capture can also be tied to a mount by calling `with_console_capture` on its wrapper, and
+//! checked through any wrapper of that mount.
+
+use std::{
+    cell::{Cell, RefCell},
+    fmt,
+    rc::Rc,
+};
+
+use wasm_bindgen::{JsCast as _, JsValue, closure::Closure};
+
+use crate::isolation;
+
+/// Turns the arguments of a console call into a single line, roughly as the console shows them
+const FORMAT_ARGS: &str = r#"
+return Array.from(args, (arg) => {
+    if (typeof arg === "string") return arg;
+    if (arg instanceof Error) return arg.stack || String(arg);
+    try {
+        return JSON.stringify(arg) ?? String(arg);
+    } catch {
+        return String(arg);
+    }
+}).join(" ");
+"#;
+
+/// Replaces `console[level]` with a function that calls `record` with the formatted arguments and
+/// then the original
+const WRAP_CONSOLE: &str = r#"
+const original = console[level];
+console[level] = function (...args) {
+    record(level, format(args));
+    return original.apply(this, args);
+};
+return original;
+"#;
+
+const LEVELS: &[ConsoleLevel] = &[
+    ConsoleLevel::Debug,
+    ConsoleLevel::Log,
+    ConsoleLevel::Info,
+    ConsoleLevel::Warn,
+    ConsoleLevel::Error,
+];
+
+/// How severe a console message is, from least to most
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConsoleLevel {
+    Debug,
+    Log,
+    Info,
+    Warn,
+    /// `console.error`, an uncaught error or an unhandled rejection
+    Error,
+}
+
+impl ConsoleLevel {
+    fn method(self) -> &'static str {
+        match self {
+            ConsoleLevel::Debug => "debug",
+            ConsoleLevel::Log => "log",
+            ConsoleLevel::Info => "info",
+            ConsoleLevel::Warn => "warn",
+            ConsoleLevel::Error => "error",
+        }
+    }
+}
+
+/// A message captured by a [`ConsoleCapture`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsoleMessage {
+    pub level: ConsoleLevel,
+    pub text: String,
+}
+
+impl fmt::Display for ConsoleMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.level.method(), self.text)
+    }
+}
+
+type Messages = Rc<RefCell<Vec<ConsoleMessage>>>;
+
+thread_local! {
+    static CONSOLE: RefCell<ConsolePatch> = RefCell::default();
+}
+
+/// The single patch of the console, which records into every capture that's alive
+#[derive(Default)]
+struct ConsolePatch {
+    next_id: usize,
+    captures: Vec<(usize, Messages)>,
+    /// The console methods from before the first capture, and what they were replaced with
+    originals: Vec<(ConsoleLevel, JsValue, JsValue)>,
+    /// Never dropped, since code may have kept a reference to the replaced console methods
+    record: Option<Closure<dyn Fn(String, String)>>,
+    error_listener: Option<Closure<dyn Fn(web_sys::Event)>>,
+}
+
+impl ConsolePatch {
+    fn install(&mut self) {
+        let format = js_sys::Function::new_with_args("args", FORMAT_ARGS);
+        let record = self
+            .record
+            .get_or_insert_with(|| Closure::new(record_console))
+            .as_ref()
+            .clone();
+        let error_listener = self
+            .error_listener
+            .get_or_insert_with(|| {
+                let format = format.clone();
+                Closure::new(move |event: web_sys::Event| record_uncaught(&format, &event))
+            })
+            .as_ref()
+            .clone();
+
+        let console = js_sys::Reflect::get(&js_sys::global(), &"console".into()).unwrap();
+        let wrap = js_sys::Function::new_with_args("console, level, record, format", WRAP_CONSOLE);
+        self.originals = LEVELS
+            .iter()
+            .map(|&level| {
+                let args = js_sys::Array::of4(&console, &level.method().into(), &record, &format);
+                let original = wrap.apply(&JsValue::NULL, &args).unwrap();
+                (level, original, get(&console, level.method()))
+            })
+            .collect();
+
+        let window = web_sys::window().unwrap();
+        isolation::internally(|| {
+            for event in ["error", "unhandledrejection"] {
+                window
+                    .add_event_listener_with_callback(event, error_listener.unchecked_ref())
+                    .unwrap();
+            }
+        });
+    }
+
+    fn uninstall(&mut self) {
+        // a method replaced again since is left alone, it still goes through the patch
+        let console = js_sys::Reflect::get(&js_sys::global(), &"console".into()).unwrap();
+        for (level, original, wrapped) in self.originals.drain(..) {
+            if get(&console, level.method()) == wrapped {
+                js_sys::Reflect::set(&console, &level.method().into(), &original).unwrap();
+            }
+        }
+
+        let window = web_sys::window().unwrap();
+        let error_listener = self.error_listener.as_ref().unwrap();
+        for event in ["error", "unhandledrejection"] {
+            window
+                .remove_event_listener_with_callback(event, error_listener.as_ref().unchecked_ref())
+                .unwrap();
+        }
+    }
+}
+
+/// Adds `message` to every capture that's alive
+fn record_message(message: ConsoleMessage) {
+    let captures = CONSOLE.with_borrow(|patch| {
+        patch
+            .captures
+            .iter()
+            .map(|(_, messages)| Rc::clone(messages))
+            .collect::<Vec<_>>()
+    });
+    for messages in captures {
+        messages.borrow_mut().push(message.clone());
+    }
+}
+
+fn record_console(level: String, text: String) {
+    let level = LEVELS
+        .iter()
+        .copied()
+        .find(|candidate| candidate.method() == level)
+        .unwrap_or(ConsoleLevel::Log);
+    record_message(ConsoleMessage { level, text });
+}
+
+fn record_uncaught(format: &js_sys::Function, event: &web_sys::Event) {
+    let (prefix, reason) = if event.type_() == "unhandledrejection" {
+        ("Uncaught (in promise)", get(event, "reason"))
+    } else {
+        let error = get(event, "error");
+        let reason = if error.is_undefined() || error.is_null() {
+            get(event, "message")
+        } else {
+            error
+        };
+        ("Uncaught", reason)
+    };
+    let text = format
+        .call1(&JsValue::NULL, &js_sys::Array::of1(&reason))
+        .unwrap()
+        .as_string()
+        .unwrap_or_default();
+
+    record_message(ConsoleMessage {
+        level: ConsoleLevel::Error,
+        text: format!("{prefix} {text}"),
+    });
+}
+
+/// Records the console until it's dropped. See the [module docs](self)
+pub struct ConsoleCapture {
+    id: usize,
+    messages: Messages,
+    /// Messages at this level or above fail the test when this is dropped
+    pub(crate) fail_on: Cell<Option<ConsoleLevel>>,
+}
+
+impl ConsoleCapture {
+    pub fn install() -> Self {
+        let messages = Messages::default();
+
+        let id = CONSOLE.with_borrow_mut(|patch| {
+            if patch.captures.is_empty() {
+                patch.install();
+            }
+
+            let id = patch.next_id;
+            patch.next_id += 1;
+            patch.captures.push((id, Rc::clone(&messages)));
+            id
+        });
+
+        Self {
+            id,
+            messages,
+            fail_on: Cell::new(None),
+        }
+    }
+
+    /// Fails the test when this is dropped if anything was printed as a warning or an error
+    pub fn strict(self) -> Self {
+        self.fail_on(ConsoleLevel::Warn)
+    }
+
+    /// Fails the test when this is dropped if anything was printed at `level` or above
+    pub fn fail_on(self, level: ConsoleLevel) -> Self {
+        self.fail_on.set(Some(level));
+        self
+    }
+
+    /// Everything captured so far, in order
+    pub fn captured_console(&self) -> Vec<ConsoleMessage> {
+        self.messages.borrow().clone()
+    }
+
+    /// Forgets everything captured so far, e.g. the expected errors of a test
+    pub fn clear(&self) {
+        self.messages.borrow_mut().clear();
+    }
+
+    /// Asserts that nothing was printed as an error, nor was there any uncaught error
+    pub fn assert_no_console_errors(&self) -> &Self {
+        self.assert_nothing_at(ConsoleLevel::Error);
+        self
+    }
+
+    /// Asserts that nothing was printed as a warning or an error
+    pub fn assert_no_console_warnings(&self) -> &Self {
+        self.assert_nothing_at(ConsoleLevel::Warn);
+        self
+    }
+
+    /// Asserts that some message at `level` contains `text`
+    pub fn assert_console_contains(&self, level: ConsoleLevel, text: &str) -> &Self {
+        let messages = self.messages.borrow();
+        assert!(
+            messages
+                .iter()
+                .any(|message| message.level == level && message.text.contains(text)),
+            "nothing containing `{text}` was printed at {level:?} level, captured:\n{}",
+            describe(messages.iter())
+        );
+        self
+    }
+
+    fn assert_nothing_at(&self, level: ConsoleLevel) {
+        let messages = self.messages.borrow();
+        let found = messages
+            .iter()
+            .filter(|message| message.level >= level)
+            .collect::<Vec<_>>();
+
+        assert!(
+            found.is_empty(),
+            "the console got {} message(s) at {level:?} level or above:\n{}",
+            found.len(),
+            describe(found.into_iter())
+        );
+    }
+}
+
+impl Drop for ConsoleCapture {
+    fn drop(&mut self) {
+        CONSOLE.with_borrow_mut(|patch| {
+            patch.captures.retain(|(id, _)| *id != self.id);
+            if patch.captures.is_empty() {
+                patch.uninstall();
+            }
+        });
+
+        if let Some(level) = self.fail_on.get() {
+            if !std::thread::panicking() {
+                self.assert_nothing_at(level);
+            }
+        }
+    }
+}
+
+fn get(target: &JsValue, key: &str) -> JsValue {
+    js_sys::Reflect::get(target, &key.into()).unwrap()
+}
+
+fn describe<'a>(messages: impl Iterator<Item = &'a ConsoleMessage>) -> String {
+    messages
+        .map(|message| format!("  - {message}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(all(test, target_family = "wasm"))]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn captures_the_console() {
+        let console = ConsoleCapture::install();
+
+        web_sys::console::log_2(&"count:".into(), &3.into());
+        web_sys::console::warn_1(&"careful".into());
+
+        assert_eq!(
+            console.captured_console(),
+            vec![
+                ConsoleMessage {
+                    level: ConsoleLevel::Log,
+                    text: "count: 3".to_string(),
+                },
+                ConsoleMessage {
+                    level: ConsoleLevel::Warn,
+                    text: "careful".to_string(),
+                },
+            ]
+        );
+        console
+            .assert_no_console_errors()
+            .assert_console_contains(ConsoleLevel::Warn, "careful");
+    }
+
+    #[wasm_bindgen_test]
+    fn captures_can_be_dropped_in_any_order() {
+        let outer = ConsoleCapture::install();
+        let inner = ConsoleCapture::install();
+        web_sys::console::log_1(&"both".into());
+
+        drop(outer);
+        web_sys::console::log_1(&"only inner".into());
+        assert_eq!(
+            inner
+                .captured_console()
+                .into_iter()
+                .map(|message| message.text)
+                .collect::<Vec<_>>(),
+            vec!["both", "only inner"]
+        );
+
+        drop(inner);
+        web_sys::console::log_1(&"none".into());
+        let again = ConsoleCapture::install();
+        web_sys::console::log_1(&"again".into());
+        assert_eq!(again.captured_console().len(), 1);
+    }
+
+    #[wasm_bindgen_test]
+    #[should_panic = "the console got 1 message(s) at Warn level or above"]
+    fn fails_strict_captures_on_drop() {
+        let _console = ConsoleCapture::install().strict();
+
+        web_sys::console::warn_1(&"a signal was read outside of a tracking context".into());
+    }
+}
@@ -0,0 +1,207 @@
+//! Recording of the events dispatched on an element and its descendants
+//!
+//! Made for checking what a component emits without wiring a callback for each event:
+//!
+//! ```ignore
+//! let editor = wrapper.query("my-editor").assert_exists();
+//! let spy = editor.spy_events(&["change", "editor:save"]);
+//!
+//! wrapper.query_as_button("button.save").assert_exists().click().await;
+//!
+//! spy.assert_fired_times("editor:save", 1).assert_not_fired("change");
+//! let saved: Draft = spy.last("editor:save").unwrap().detail_json();
+//! ```
+
+use std::{cell::RefCell, rc::Rc};
+
+use serde::de::DeserializeOwned;
+use wasm_bindgen::{JsCast as _, JsValue, closure::Closure};
+
+use crate::isolation;
+
+/// An event recorded by an [`EventSpy`]
+#[derive(Debug, Clone)]
+pub struct SpiedEvent(web_sys::Event);
+
+impl SpiedEvent {
+    /// The underlying event
+    pub fn event(&self) -> &web_sys::Event {
+        &self.0
+    }
+
+    /// The type of the event, e.g. `click`
+    pub fn name(&self) -> String {
+        self.0.type_()
+    }
+
+    /// The element the event was dispatched on
+    pub fn target(&self) -> Option<web_sys::Element> {
+        self.0.target().and_then(|target| target.dyn_into().ok())
+    }
+
+    /// The `detail` of a `CustomEvent`, `undefined` for other events
+    pub fn detail(&self) -> JsValue {
+        self.0
+            .dyn_ref::<web_sys::CustomEvent>()
+            .map(web_sys::CustomEvent::detail)
+            .unwrap_or(JsValue::UNDEFINED)
+    }
+
+    /// The `detail` of a `CustomEvent`, deserialized from its JSON form
+    ///
+    /// panics if it can't be deserialized as `T`
+    pub fn detail_json<T: DeserializeOwned>(&self) -> T {
+        let json = js_sys::JSON::stringify(&self.detail())
+            .ok()
+            .and_then(|json| json.as_string())
+            .unwrap_or_else(|| panic!("the detail of `{}` can't be turned to JSON", self.name()));
+
+        serde_json::from_str(&json).unwrap_or_else(|err| {
+            panic!(
+                "the detail of `{}` (`{json}`) can't be deserialized: {err}",
+                self.name()
+            )
+        })
+    }
+
+    /// Any property of the event, e.g. `key` for keyboard events
+    pub fn property(&self, key: &str) -> JsValue {
+        js_sys::Reflect::get(&self.0, &key.into()).unwrap()
+    }
+}
+
+struct Listener {
+    name: String,
+    capture: bool,
+    callback: Closure<dyn Fn(web_sys::Event)>,
+}
+
+/// Records events until it's dropped. See the [module docs](self)
+pub struct EventSpy {
+    target: web_sys::EventTarget,
+    names: Vec<String>,
+    calls: Rc<RefCell<Vec<SpiedEvent>>>,
+    listeners: Vec<Listener>,
+}
+
+impl EventSpy {
+    /// Starts recording the events called `names` dispatched on `target` or its descendants
+    pub fn new(target: &web_sys::EventTarget, names: &[&str]) -> Self {
+        let calls = Rc::new(RefCell::new(Vec::<SpiedEvent>::new()));
+
+        // listening in both phases catches events that don't bubble as well as the ones stopped
+        // before reaching the target, an event seen in both is only recorded once
+        let listeners = names
+            .iter()
+            .flat_map(|name| [(name, true), (name, false)])
+            .map(|(name, capture)| {
+                let calls = Rc::clone(&calls);
+                let callback = Closure::<dyn Fn(web_sys::Event)>::new(move |event| {
+                    let mut calls = calls.borrow_mut();
+                    if !calls.iter().any(|call| call.0 == event) {
+                        calls.push(SpiedEvent(event));
+                    }
+                });
+                isolation::internally(|| {
+                    target
+                        .add_event_listener_with_callback_and_bool(
+                            name,
+                            callback.as_ref().unchecked_ref(),
+                            capture,
+                        )
+                        .unwrap();
+                });
+
+                Listener {
+                    name: name.to_string(),
+                    capture,
+                    callback,
+                }
+            })
+            .collect();
+
+        Self {
+            target: target.clone(),
+            names: names.iter().map(|name| name.to_string()).collect(),
+            calls,
+            listeners,
+        }
+    }
+
+    /// Every recorded event, in order
+    pub fn calls(&self) -> Vec<SpiedEvent> {
+        self.calls.borrow().clone()
+    }
+
+    /// The recorded events called `name`, in order
+    pub fn calls_of(&self, name: &str) -> Vec<SpiedEvent> {
+        self.assert_spied(name);
+        self.calls
+            .borrow()
+            .iter()
+            .filter(|call| call.name() == name)
+            .cloned()
+            .collect()
+    }
+
+    /// The last recorded event called `name`
+    pub fn last(&self, name: &str) -> Option<SpiedEvent> {
+        self.calls_of(name).pop()
+    }
+
+    /// Forgets every recorded event
+    pub fn clear(&self) {
+        self.calls.borrow_mut().clear();
+    }
+
+    /// Asserts that `name` was fired at least once
+    pub fn assert_fired(&self, name: &str) -> &Self {
+        assert!(
+            !self.calls_of(name).is_empty(),
+            "`{name}` wasn't fired, got: {:?}",
+            self.fired_names()
+        );
+        self
+    }
+
+    /// Asserts that `name` was fired exactly `times` times
+    pub fn assert_fired_times(&self, name: &str, times: usize) -> &Self {
+        let fired = self.calls_of(name).len();
+        assert_eq!(
+            fired, times,
+            "`{name}` was fired {fired} time(s) instead of {times}"
+        );
+        self
+    }
+
+    /// Asserts that `name` was never fired
+    pub fn assert_not_fired(&self, name: &str) -> &Self {
+        self.assert_fired_times(name, 0)
+    }
+
+    fn assert_spied(&self, name: &str) {
+        assert!(
+            self.names.iter().any(|spied| spied == name),
+            "`{name}` isn't spied on, only {:?} are",
+            self.names
+        );
+    }
+
+    fn fired_names(&self) -> Vec<String> {
+        self.calls.borrow().iter().map(SpiedEvent::name).collect()
+    }
+}
+
+impl Drop for EventSpy {
+    fn drop(&mut self) {
+        for listener in &self.listeners {
+            self.target
+                .remove_event_listener_with_callback_and_bool(
+                    &listener.name,
+                    listener.callback.as_ref().unchecked_ref(),
+                    listener.capture,
+                )
+                .unwrap();
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use std::rc::Rc;
+
+use futures_util::future::AbortHandle;
+
+use crate::{framework::Framework, isolation::TestRoot, task::until_quiescent};
+
+pub struct Dioxus;
+
+impl Framework for Dioxus {
+    type Context = Rc<AppGuard>;
+
+    /// Dioxus diffs and applies mutations on its own task, which may schedule more work (effects,
+    /// spawned futures), so this yields to it until a whole round passes without any changes to the DOM
+    async fn settle(ctx: &Self::Context) {
+        until_quiescent(ctx.root.element()).await;
+    }
+}
+
+/// Keeps a Dioxus app running and stops it (and removes its root) when the last wrapper holding it is
+/// dropped
+pub struct AppGuard {
+    pub(super) abort_handle: AbortHandle,
+    pub(super) root: TestRoot,
+}
+
+impl Drop for AppGuard {
+    fn drop(&mut self) {
+        self.abort_handle.abort();
+    }
+}
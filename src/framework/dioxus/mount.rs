@@ -0,0 +1,105 @@
+use std::rc::Rc;
+
+use dioxus::prelude::*;
+use futures_util::future::{AbortHandle, Abortable};
+
+use super::implementation::{AppGuard, Dioxus};
+use crate::{isolation::TestRoot, task::until_quiescent, wrapper::BaseTestWrapper};
+
+/// Launches an app into a new element on the dom and returns a [`BaseTestWrapper`] for working with it
+///
+/// It's async because Dioxus renders on its own task, so the DOM is empty until it runs
+pub async fn mount_test(app: fn() -> Element) -> BaseTestWrapper<Dioxus> {
+    mount_virtual_dom(VirtualDom::new(app)).await
+}
+
+/// Launches a component with the given props into a new element on the dom and returns a
+/// [`BaseTestWrapper`] for working with it
+pub async fn mount_test_with_props<P, M>(
+    component: impl dioxus::core::ComponentFunction<P, M>,
+    props: P,
+) -> BaseTestWrapper<Dioxus>
+where
+    P: Clone + 'static,
+    M: 'static,
+{
+    mount_virtual_dom(VirtualDom::new_with_props(component, props)).await
+}
+
+async fn mount_virtual_dom(vdom: VirtualDom) -> BaseTestWrapper<Dioxus> {
+    let root = TestRoot::new();
+    let test_root_node = root.element().clone();
+
+    let config = dioxus::web::Config::new().rootelement(test_root_node.clone());
+    let (abort_handle, abort_registration) = AbortHandle::new_pair();
+    wasm_bindgen_futures::spawn_local(async move {
+        let _ = Abortable::new(dioxus::web::run(vdom, config), abort_registration).await;
+    });
+    until_quiescent(&test_root_node).await;
+
+    BaseTestWrapper::with_root(test_root_node, Rc::new(AppGuard { abort_handle, root }))
+}
+
+#[cfg(all(test, target_family = "wasm"))]
+mod test {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn counter() -> Element {
+        let mut count = use_signal(|| 0);
+
+        rsx! {
+            span { id: "count", "{count}" }
+            button { onclick: move |_| count += 1, "increment" }
+        }
+    }
+
+    #[component]
+    fn Greeting(name: String) -> Element {
+        rsx! { span { id: "greeting", "Hello {name}" } }
+    }
+
+    #[wasm_bindgen_test]
+    async fn actually_mounts_it() {
+        let test_wrapper = mount_test(|| {
+            rsx! { span { id: "mounted-span", "hi" } }
+        })
+        .await;
+
+        test_wrapper.query("#mounted-span").assert_exists();
+    }
+
+    #[wasm_bindgen_test]
+    async fn mounts_with_props() {
+        let test_wrapper = mount_test_with_props(
+            Greeting,
+            GreetingProps {
+                name: String::from("world"),
+            },
+        )
+        .await;
+
+        test_wrapper
+            .query("#greeting")
+            .assert_exists()
+            .assert_text_is("Hello world");
+    }
+
+    #[wasm_bindgen_test]
+    async fn settles_after_interactions() {
+        let test_wrapper = mount_test(counter).await;
+
+        test_wrapper
+            .query_as_button("button")
+            .assert_exists()
+            .click()
+            .await;
+
+        test_wrapper
+            .query("#count")
+            .assert_exists()
+            .assert_text_is("1");
+    }
+}
@@ -0,0 +1,280 @@
+use std::{
+    fmt::Debug,
+    future::Future,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use leptos::{prelude::*, server_fn::ServerFn};
+use serde::de::DeserializeOwned;
+
+use super::implementation::Leptos;
+use crate::{
+    task, timers,
+    wrapper::{TestWrapper, TestWrapperState},
+};
+
+/// How long [`TestWrapper::wait_for_action`] waits for an action to finish
+const ACTION_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// An [`Action`] to hand to the view under test, which the test can then check on
+///
+/// ```ignore
+/// let (add_todo, server_action) = TestAction::<AddTodo, _>::server();
+/// let _mock = mock_server_fn::<AddTodo>(async |args| Ok(args.title));
+/// let wrapper = mount_test(move || view! { <NewTodo action=server_action /> });
+///
+/// wrapper.query_as_form("form").assert_exists().submit().await;
+/// wrapper.wait_for_action(&add_todo).await;
+///
+/// add_todo.assert_dispatched(1).assert_value(&Ok("Buy milk".to_string()));
+/// ```
+pub struct TestAction<I: 'static, O: 'static> {
+    action: Action<I, O>,
+    dispatches: Dispatches,
+}
+
+enum Dispatches {
+    /// Counted by the action itself
+    Counted(Arc<AtomicUsize>),
+    /// Counted by the action's version, which only goes up when a dispatch finishes
+    Version,
+}
+
+impl<I, O> TestAction<I, O>
+where
+    I: Send + Sync + 'static,
+    O: Send + Sync + 'static,
+{
+    /// Creates an action as with [`Action::new`], which also counts how many times it's dispatched
+    pub fn new<F, Fu>(action_fn: F) -> Self
+    where
+        F: Fn(&I) -> Fu + Send + Sync + 'static,
+        Fu: Future<Output = O> + Send + 'static,
+    {
+        let dispatches = Arc::new(AtomicUsize::new(0));
+        let dispatches_clone = Arc::clone(&dispatches);
+        let action = Action::new(move |input: &I| {
+            dispatches_clone.fetch_add(1, Ordering::Relaxed);
+            action_fn(input)
+        });
+
+        Self {
+            action,
+            dispatches: Dispatches::Counted(dispatches),
+        }
+    }
+
+    /// The action to pass to the view
+    pub fn action(&self) -> Action<I, O> {
+        self.action
+    }
+}
+
+impl<F> TestAction<F, Result<F::Output, F::Error>>
+where
+    F: ServerFn + DeserializeOwned + Clone + Send + Sync + 'static,
+    F::Output: Send + Sync + 'static,
+    F::Error: Send + Sync + 'static,
+{
+    /// Creates a [`ServerAction`] to pass to the view (e.g. to an `<ActionForm>`) and a handle to
+    /// check on it
+    ///
+    /// Its dispatches are counted through [`Action::version`], so only the ones that finished count
+    /// (and not the ones overtaken by a later dispatch before finishing), which
+    /// [`TestWrapper::wait_for_action`] waits for
+    pub fn server() -> (Self, ServerAction<F>) {
+        let server_action = ServerAction::<F>::new();
+        let test_action = Self {
+            action: server_action.into(),
+            dispatches: Dispatches::Version,
+        };
+
+        (test_action, server_action)
+    }
+}
+
+impl<I: 'static, O: 'static> TestAction<I, O> {
+    /// Creates an action as with [`Action::new_local`], which also counts how many times it's
+    /// dispatched
+    pub fn new_local<F, Fu>(action_fn: F) -> Self
+    where
+        F: Fn(&I) -> Fu + 'static,
+        Fu: Future<Output = O> + 'static,
+    {
+        let dispatches = Arc::new(AtomicUsize::new(0));
+        let dispatches_clone = Arc::clone(&dispatches);
+        let action = Action::new_local(move |input: &I| {
+            dispatches_clone.fetch_add(1, Ordering::Relaxed);
+            action_fn(input)
+        });
+
+        Self {
+            action,
+            dispatches: Dispatches::Counted(dispatches),
+        }
+    }
+
+    /// How many times the action was dispatched
+    pub fn dispatch_count(&self) -> usize {
+        match &self.dispatches {
+            Dispatches::Counted(count) => count.load(Ordering::Relaxed),
+            Dispatches::Version => self.action.version().get_untracked(),
+        }
+    }
+
+    /// Whether the action is still waiting for some dispatch to finish
+    pub fn is_pending(&self) -> bool {
+        self.action.pending().get_untracked()
+    }
+
+    /// The value returned by the last dispatch that finished
+    pub fn value(&self) -> Option<O>
+    where
+        O: Clone + Send + Sync,
+    {
+        self.action.value().get_untracked()
+    }
+
+    pub fn assert_dispatched(&self, times: usize) -> &Self {
+        assert_eq!(
+            self.dispatch_count(),
+            times,
+            "action was dispatched an unexpected number of times"
+        );
+        self
+    }
+
+    pub fn assert_value(&self, expected: &O) -> &Self
+    where
+        O: Clone + Send + Sync + PartialEq + Debug,
+    {
+        assert_eq!(
+            self.value().as_ref(),
+            Some(expected),
+            "unexpected value of the last dispatch"
+        );
+        self
+    }
+}
+
+impl<State: TestWrapperState> TestWrapper<State, Leptos> {
+    /// Waits until `action` isn't pending anymore and the view has caught up with its value
+    ///
+    /// Panics if the action is still pending after a second
+    pub async fn wait_for_action<I: 'static, O: 'static>(
+        &self,
+        action: &TestAction<I, O>,
+    ) -> &Self {
+        self.wait_for_action_within(action, ACTION_TIMEOUT).await
+    }
+
+    /// [`Self::wait_for_action`], giving up after `timeout` instead of a second
+    pub async fn wait_for_action_within<I: 'static, O: 'static>(
+        &self,
+        action: &TestAction<I, O>,
+        timeout: Duration,
+    ) -> &Self {
+        let deadline = timers::real_now() + timeout.as_secs_f64() * 1000.0;
+
+        self.settle().await;
+        while action.is_pending() {
+            if timers::real_now() > deadline {
+                panic!("action was still pending after {timeout:?}");
+            }
+
+            task::next_macrotask().await;
+        }
+        self.settle().await;
+
+        self
+    }
+}
+
+#[cfg(all(test, target_family = "wasm"))]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::framework::leptos::{mock_server_fn, mount_test};
+    use leptos::{form::ActionForm, view};
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[server]
+    async fn add_todo(title: String) -> Result<usize, ServerFnError> {
+        unreachable!("only called through mocks in tests")
+    }
+
+    #[wasm_bindgen_test]
+    async fn waits_for_dispatches() {
+        let double = TestAction::new_local(|input: &i32| {
+            let input = *input;
+            async move {
+                task::sleep(Duration::from_millis(20)).await;
+                input * 2
+            }
+        });
+
+        let action = double.action();
+        let wrapper = mount_test(move || {
+            view! {
+                <button on:click=move |_| {
+                    action.dispatch(21);
+                }>double</button>
+                <p>{move || action.value().get()}</p>
+            }
+        });
+
+        wrapper
+            .query_as_button("button")
+            .assert_exists()
+            .click()
+            .await;
+        assert!(double.is_pending());
+
+        wrapper.wait_for_action(&double).await;
+
+        double.assert_dispatched(1).assert_value(&42);
+        wrapper.query("p").assert_exists().assert_text_is("42");
+    }
+
+    #[wasm_bindgen_test]
+    async fn submits_action_forms() {
+        let add_todo_mock = mock_server_fn::<AddTodo>(async |args| Ok(args.title.len()));
+        let (add_todo_action, server_action) = TestAction::<AddTodo, _>::server();
+        // calls from elsewhere aren't dispatches of the action
+        add_todo("unrelated".to_string()).await.unwrap();
+
+        let wrapper = mount_test(move || {
+            view! {
+                <ActionForm action=server_action>
+                    <input name="title" value="Buy milk" />
+                </ActionForm>
+            }
+        });
+
+        wrapper.query_as_form("form").assert_exists().submit().await;
+        wrapper.wait_for_action(&add_todo_action).await;
+
+        add_todo_action.assert_dispatched(1).assert_value(&Ok(8));
+        add_todo_mock.assert_called(2, None);
+    }
+
+    #[wasm_bindgen_test]
+    #[should_panic = "action was still pending after 50ms"]
+    async fn gives_up_on_pending_actions() {
+        let never = TestAction::new_local(|_: &()| std::future::pending::<()>());
+
+        let wrapper = mount_test(|| view! { <p>"waiting"</p> });
+        never.action().dispatch(());
+
+        wrapper
+            .wait_for_action_within(&never, Duration::from_millis(50))
+            .await;
+    }
+}
@@ -0,0 +1,188 @@
+use std::{rc::Rc, sync::Arc};
+
+use hydration_context::{HydrateSharedContext, SsrSharedContext};
+use leptos::{
+    prelude::*,
+    tachys::{hydration::Cursor, view::PositionState},
+};
+use wasm_bindgen::JsCast as _;
+
+use super::{
+    implementation::{ErasedDestructor, Leptos, MountedView},
+    leaks, suspense,
+};
+use crate::{
+    isolation::TestRoot,
+    snapshot::{self, SnapshotOptions},
+    wrapper::BaseTestWrapper,
+};
+
+/// Renders a view to HTML as the server would, then hydrates it in a new element on the dom and
+/// returns a [`BaseTestWrapper`] for working with it
+///
+/// Before hydrating, the server HTML is compared to what the client renders for the same view, and
+/// any difference fails the test with a diff pointing at the divergent nodes. The hydration itself
+/// fails the test too if Leptos finds a node it didn't expect
+///
+/// Both renders happen in the browser, but the server one runs with a server-side shared context, so
+/// code that checks e.g. [`Owner::current_shared_context`] behaves as it would on the server
+///
+/// # The view runs three times
+///
+/// `f` is called for the server render, for the client render it's compared with, and once more to
+/// hydrate. Hydrating can't stand in for the client render, since it adopts the server's nodes
+/// instead of creating its own. Whatever the view does while running happens three times too:
+/// server functions are called three times (so a mocked one counts three calls per mount),
+/// resources load three times, and counters or spies in `f` see three runs. Only the last run
+/// stays alive, so compare counts after mounting against a baseline taken right after
+/// `mount_hydrated_test` rather than against zero
+pub fn mount_hydrated_test<F, V>(f: F) -> BaseTestWrapper<Leptos>
+where
+    F: Fn() -> V + 'static,
+    V: IntoView,
+    <V as Render>::State: 'static,
+{
+    suspense::install_tracker();
+    let leak_checkpoint = leaks::checkpoint();
+
+    let f = Rc::new(f);
+    let server_html = render_on_server(&*f);
+    let test_root = TestRoot::new();
+    let test_root_node = test_root.element().clone();
+    test_root_node.set_inner_html(&server_html);
+
+    assert_no_mismatch(&test_root_node, &f);
+
+    let owner = Owner::new_root(Some(Arc::new(HydrateSharedContext::new())));
+    let mountable = owner.with(|| {
+        suspense::provide_root_context();
+        f().into_view().hydrate::<true>(
+            &Cursor::new(test_root_node.clone().unchecked_into()),
+            &PositionState::default(),
+        )
+    });
+    if let Some(shared_context) = owner.shared_context() {
+        shared_context.hydration_complete();
+    }
+
+    BaseTestWrapper::with_root(
+        test_root_node,
+        Rc::new(MountedView::new(
+            HydratedView(mountable),
+            owner,
+            test_root,
+            leak_checkpoint,
+        )),
+    )
+}
+
+/// The view hydrated by [`mount_hydrated_test`], unmounted on drop like an [`UnmountHandle`]
+struct HydratedView<M: Mountable>(M);
+
+impl<M: Mountable> Drop for HydratedView<M> {
+    fn drop(&mut self) {
+        self.0.unmount();
+    }
+}
+
+impl<M: Mountable + 'static> ErasedDestructor for HydratedView<M> {}
+
+fn render_on_server<F, V>(f: &F) -> String
+where
+    F: Fn() -> V,
+    V: IntoView,
+{
+    let owner = Owner::new_root(Some(Arc::new(SsrSharedContext::new())));
+    let html = owner.with(|| f().into_view().to_html());
+    owner.cleanup();
+    html
+}
+
+/// Panics if the server HTML in `root` differs from what the client renders for the same view
+fn assert_no_mismatch<F, V>(root: &web_sys::Element, f: &Rc<F>)
+where
+    F: Fn() -> V + 'static,
+    V: IntoView,
+    <V as Render>::State: 'static,
+{
+    let client_root = document().create_element("section").unwrap();
+    let f = Rc::clone(f);
+    let handle = mount_to(client_root.clone().unchecked_into(), move || f());
+
+    let server = comparable_html(root);
+    let client = comparable_html(&client_root);
+    drop(handle);
+
+    if server != client {
+        panic!(
+            "hydration mismatch, the server HTML (-) differs from what the client renders (+):\n{}",
+            snapshot::line_diff(&server, &client)
+        );
+    }
+}
+
+/// Serializes the children of `root` without the hydration markers, which only the server renders
+fn comparable_html(root: &web_sys::Element) -> String {
+    let root = root.clone_node_with_deep(true).unwrap();
+    remove_comments(&root);
+    // the markers split text nodes apart, so they have to be merged again
+    root.normalize();
+
+    snapshot::serialize(&root, &SnapshotOptions::default())
+}
+
+fn remove_comments(node: &web_sys::Node) {
+    use crate::util::NodeListExt as _;
+
+    for child in node.child_nodes().into_iterator() {
+        if child.node_type() == web_sys::Node::COMMENT_NODE {
+            node.remove_child(&child).unwrap();
+        } else {
+            remove_comments(&child);
+        }
+    }
+}
+
+#[cfg(all(test, target_family = "wasm"))]
+mod tests {
+    use super::*;
+    use leptos::view;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn is_server() -> bool {
+        Owner::current_shared_context().is_some_and(|sc| !sc.is_browser())
+    }
+
+    #[wasm_bindgen_test]
+    async fn hydrates_server_html() {
+        let wrapper = mount_hydrated_test(|| {
+            let count = RwSignal::new(0);
+            view! {
+                <p>"Count: " {count}</p>
+                <button on:click=move |_| count.update(|count| *count += 1)>increment</button>
+            }
+        });
+
+        wrapper
+            .query_as_button("button")
+            .assert_exists()
+            .click()
+            .await;
+
+        wrapper
+            .query("p")
+            .assert_exists()
+            .assert_text_is("Count: 1");
+    }
+
+    #[wasm_bindgen_test]
+    #[should_panic = "hydration mismatch"]
+    fn fails_on_mismatches() {
+        mount_hydrated_test(|| {
+            let place = if is_server() { "server" } else { "client" };
+            view! { <p>"Rendered on the " {place}</p> }
+        });
+    }
+}
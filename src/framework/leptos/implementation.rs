@@ -1,12 +1,48 @@
 use leptos::prelude::*;
-use std::sync::Arc;
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
 
-use crate::framework::Framework;
+use crate::{
+    framework::Framework,
+    isolation::TestRoot,
+    wrapper::{TestWrapper, TestWrapperState},
+};
 
 pub struct Leptos;
 
 impl Framework for Leptos {
-    type Context = Arc<dyn ErasedDestructor>;
+    type Context = Rc<MountedView>;
+
+    async fn settle(_ctx: &Self::Context) {
+        leptos::task::tick().await;
+    }
+}
+
+/// Settles a wrapper, see [`TestWrapper::settle_fn`]
+pub(super) type SettleFn = Rc<dyn Fn() -> Pin<Box<dyn Future<Output = ()>>>>;
+
+impl<State: TestWrapperState> TestWrapper<State, Leptos> {
+    /// A function that settles like [`Self::settle`], for what has to settle after the wrapper is
+    /// borrowed
+    pub(super) fn settle_fn(&self) -> SettleFn {
+        let strategy = Rc::clone(&self.settle_strategy);
+        let ctx = Rc::clone(self.framework_ctx());
+        let root = self.root().clone();
+        Rc::new(move || {
+            let strategy = strategy.get();
+            let ctx = Rc::clone(&ctx);
+            let root = root.clone();
+            Box::pin(async move { strategy.settle::<Leptos>(&ctx, &root).await })
+        })
+    }
 }
 
 /// A dumy trait just to get some sort of `dyn Drop`
@@ -14,3 +50,55 @@ pub trait ErasedDestructor: 'static {}
 /// The only think we're interested about the handle is its [`Drop`] impl, so we erase it
 /// to avoid generics in the [`Framework`] struct
 impl<T: Mountable + 'static> ErasedDestructor for UnmountHandle<T> {}
+
+/// A view mounted for a test, which stays mounted until it's unmounted explicitly or every wrapper
+/// using it is dropped
+///
+/// Its root is only removed from the dom once every wrapper is dropped, so it can still be inspected
+/// after unmounting
+pub struct MountedView {
+    destructor: RefCell<Option<Box<dyn ErasedDestructor>>>,
+    /// The reactive owner the view was mounted under
+    owner: Owner,
+    disposed: Arc<AtomicBool>,
+    /// See [`super::leaks::checkpoint`]
+    pub(super) leak_checkpoint: u64,
+    _root: TestRoot,
+}
+
+impl MountedView {
+    pub(super) fn new(
+        destructor: impl ErasedDestructor,
+        owner: Owner,
+        root: TestRoot,
+        leak_checkpoint: u64,
+    ) -> Self {
+        let disposed = Arc::new(AtomicBool::new(false));
+        let disposed_clone = Arc::clone(&disposed);
+        owner.with(|| on_cleanup(move || disposed_clone.store(true, Ordering::Relaxed)));
+
+        Self {
+            destructor: RefCell::new(Some(Box::new(destructor))),
+            owner,
+            disposed,
+            leak_checkpoint,
+            _root: root,
+        }
+    }
+
+    pub fn owner(&self) -> &Owner {
+        &self.owner
+    }
+
+    /// Whether the reactive owner of the view was cleaned up
+    pub fn is_disposed(&self) -> bool {
+        self.disposed.load(Ordering::Relaxed)
+    }
+
+    /// Removes the view from the DOM and disposes of its reactive owner
+    pub(super) fn unmount(&self) {
+        let destructor = self.destructor.borrow_mut().take();
+        drop(destructor);
+        self.owner.cleanup();
+    }
+}
@@ -0,0 +1,347 @@
+//! Tracking of the `window`/`document` event listeners and the intervals a view leaves behind
+//!
+//! Leptos helpers like `window_event_listener` and `set_interval` go through the browser's
+//! `addEventListener` and `setInterval`, so those (and their counterparts for removing) are wrapped
+//! once per page to record what's currently registered. What's registered under the reactive owner
+//! of another mount, or by the library itself (like a console capture), isn't blamed on a view. Intervals are observed through the same
+//! patch as [`FakeTimers`](crate::timers::FakeTimers), so they're tracked whether they're faked or
+//! not.
+
+use std::cell::RefCell;
+
+use wasm_bindgen::{JsCast as _, JsValue, closure::Closure};
+
+use leptos::prelude::Owner;
+
+use super::implementation::Leptos;
+use crate::{
+    isolation, timers,
+    wrapper::{TestWrapper, TestWrapperState},
+};
+
+thread_local! {
+    static TRACKER: RefCell<Option<Tracker>> = const { RefCell::new(None) };
+}
+
+#[derive(Default)]
+struct Tracker {
+    /// Increases with every registration, to tell which ones happened after some point
+    next_seq: u64,
+    listeners: Vec<Listener>,
+    intervals: Vec<Interval>,
+}
+
+struct Listener {
+    seq: u64,
+    owner: Option<Vec<usize>>,
+    target: &'static str,
+    event: String,
+    callback: JsValue,
+    capture: bool,
+}
+
+struct Interval {
+    seq: u64,
+    owner: Option<Vec<usize>>,
+    id: JsValue,
+}
+
+/// The ids of the current reactive owner and its ancestors, if there's an owner
+///
+/// Taken right away, since an owner only keeps weak references to its parent
+fn current_owner() -> Option<Vec<usize>> {
+    let owner = Owner::current()?;
+    let mut ids = owner.ancestry();
+    ids.push(owner.debug_id());
+    Some(ids)
+}
+
+/// Whether something registered under `owner` may belong to the mount with the root owner `root`
+fn belongs_to(owner: &Option<Vec<usize>>, root: usize) -> bool {
+    // without an owner, there's no telling which mount it comes from
+    owner.as_ref().is_none_or(|ids| ids.contains(&root))
+}
+
+/// Installs the tracking if it isn't yet and returns a checkpoint, so [`leaks_since`] only reports
+/// what's registered after it
+pub(super) fn checkpoint() -> u64 {
+    let installed = TRACKER.with_borrow(Option::is_some);
+    if !installed {
+        TRACKER.set(Some(Tracker::default()));
+
+        let window = web_sys::window().unwrap();
+        let document = window.document().unwrap();
+        track_listeners(&window, "window");
+        track_listeners(&document, "document");
+        track_intervals();
+    }
+
+    TRACKER.with_borrow(|tracker| tracker.as_ref().unwrap().next_seq)
+}
+
+/// Descriptions of the listeners and intervals registered after `checkpoint` that are still there,
+/// leaving out the ones registered under another root owner than `root`
+fn leaks_since(checkpoint: u64, root: usize) -> Vec<String> {
+    TRACKER.with_borrow(|tracker| {
+        let Some(tracker) = tracker else {
+            return Vec::new();
+        };
+
+        let listeners = tracker
+            .listeners
+            .iter()
+            .filter(|listener| listener.seq >= checkpoint && belongs_to(&listener.owner, root))
+            .map(|listener| format!("`{}` listener on {}", listener.event, listener.target));
+        let intervals = tracker
+            .intervals
+            .iter()
+            .filter(|interval| interval.seq >= checkpoint && belongs_to(&interval.owner, root))
+            .map(|interval| format!("interval {}", interval.id.as_f64().unwrap_or_default()));
+
+        listeners.chain(intervals).collect()
+    })
+}
+
+fn with_tracker(f: impl FnOnce(&mut Tracker)) {
+    TRACKER.with_borrow_mut(|tracker| f(tracker.as_mut().unwrap()));
+}
+
+fn track_listeners(target: &JsValue, target_name: &'static str) {
+    wrap_method(target, "addEventListener", move |args, _| {
+        if isolation::is_internal() {
+            return;
+        }
+
+        let listener = listener_from_args(target_name, &args);
+        with_tracker(|tracker| {
+            tracker.listeners.push(Listener {
+                seq: tracker.next_seq,
+                ..listener
+            });
+            tracker.next_seq += 1;
+        });
+    });
+    wrap_method(target, "removeEventListener", move |args, _| {
+        let removed = listener_from_args(target_name, &args);
+        with_tracker(|tracker| {
+            tracker.listeners.retain(|listener| {
+                listener.target != removed.target
+                    || listener.event != removed.event
+                    || listener.callback != removed.callback
+                    || listener.capture != removed.capture
+            });
+        });
+    });
+}
+
+fn listener_from_args(target: &'static str, args: &js_sys::Array) -> Listener {
+    let options = args.get(2);
+    let capture = if options.is_object() {
+        js_sys::Reflect::get(&options, &"capture".into())
+            .unwrap()
+            .is_truthy()
+    } else {
+        options.is_truthy()
+    };
+
+    Listener {
+        seq: 0,
+        owner: current_owner(),
+        target,
+        event: args.get(0).as_string().unwrap_or_default(),
+        callback: args.get(1),
+        capture,
+    }
+}
+
+fn track_intervals() {
+    timers::observe("setInterval", |_, id| {
+        with_tracker(|tracker| {
+            tracker.intervals.push(Interval {
+                seq: tracker.next_seq,
+                owner: current_owner(),
+                id,
+            });
+            tracker.next_seq += 1;
+        });
+    });
+    timers::observe("clearInterval", |args, _| {
+        let id = args.get(0);
+        with_tracker(|tracker| tracker.intervals.retain(|interval| interval.id != id));
+    });
+}
+
+/// Replaces `target[name]` with a function that calls the original and then `record` with the
+/// arguments and the result. The wrapper stays in place for the rest of the page's life
+fn wrap_method(target: &JsValue, name: &str, record: impl Fn(js_sys::Array, JsValue) + 'static) {
+    let record = Closure::<dyn Fn(js_sys::Array, JsValue)>::new(record);
+    let wrap = js_sys::Function::new_with_args(
+        "target, name, record",
+        "const original = target[name];
+        target[name] = function (...args) {
+            const result = original.apply(this, args);
+            record(args, result);
+            return result;
+        };",
+    );
+    wrap.call3(
+        &JsValue::NULL,
+        target,
+        &JsValue::from_str(name),
+        record.as_ref().unchecked_ref(),
+    )
+    .unwrap();
+    record.forget();
+}
+
+impl<State: TestWrapperState> TestWrapper<State, Leptos> {
+    /// Removes the view from the DOM and disposes of its reactive owner, then waits for the cleanup
+    /// to be applied
+    ///
+    /// The wrappers for this view can still be used afterwards, e.g. for [`Self::assert_no_leaks`]
+    pub async fn unmount(&self) -> &Self {
+        self.framework_ctx().unmount();
+        self.settle().await;
+
+        self
+    }
+
+    /// Descriptions of what an unmounted view left behind, see [`Self::assert_no_leaks`]
+    pub fn leaks(&self) -> Vec<String> {
+        let mut leaks = Vec::new();
+
+        if self.root().child_nodes().length() > 0 {
+            leaks.push(format!(
+                "the root still has content: {}",
+                self.root().inner_html()
+            ));
+        }
+        if !self.framework_ctx().is_disposed() {
+            leaks.push("the reactive owner wasn't disposed".to_string());
+        }
+        leaks.extend(leaks_since(
+            self.framework_ctx().leak_checkpoint,
+            self.framework_ctx().owner().debug_id(),
+        ));
+
+        leaks
+    }
+
+    /// Asserts that an unmounted view left nothing behind: the root is empty, the reactive owner
+    /// (and so every effect under it) was disposed, and there are no `window`/`document` event
+    /// listeners or intervals left that were registered since the view was mounted
+    pub fn assert_no_leaks(&self) -> &Self {
+        let leaks = self.leaks();
+        assert!(
+            leaks.is_empty(),
+            "the view leaked after unmounting:\n  - {}",
+            leaks.join("\n  - ")
+        );
+
+        self
+    }
+}
+
+#[cfg(all(test, target_family = "wasm"))]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{framework::leptos::mount_test, timers::FakeTimers};
+    use leptos::{ev, prelude::*, view};
+    use wasm_bindgen::{JsCast as _, closure::Closure};
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    async fn unmounts_cleanly() {
+        let wrapper = mount_test(|| {
+            let width = RwSignal::new(0);
+            let handle = window_event_listener(ev::resize, move |_| width.set(1));
+            on_cleanup(move || handle.remove());
+            let interval = set_interval_with_handle(|| {}, Duration::from_millis(100)).unwrap();
+            on_cleanup(move || interval.clear());
+
+            view! { <p>{width}</p> }
+        });
+
+        wrapper.unmount().await.assert_no_leaks();
+    }
+
+    #[wasm_bindgen_test]
+    #[should_panic = "`resize` listener on window"]
+    async fn reports_leaked_listeners() {
+        let wrapper = mount_test(|| {
+            let width = RwSignal::new(0);
+            // the handle is never removed
+            let _ = window_event_listener(ev::resize, move |_| width.set(1));
+
+            view! { <p>{width}</p> }
+        });
+
+        wrapper.unmount().await.assert_no_leaks();
+    }
+
+    #[wasm_bindgen_test]
+    async fn reports_leaked_fake_intervals() {
+        let timers = FakeTimers::install();
+        let wrapper = mount_test(|| {
+            // the handle is never cleared
+            let _ = set_interval_with_handle(|| {}, Duration::from_millis(100)).unwrap();
+
+            view! { <p>"polling"</p> }
+        });
+
+        let leaks = wrapper.unmount().await.leaks();
+        // the fakes have to go before anything can fail
+        drop(timers);
+
+        assert_eq!(leaks.len(), 1, "{leaks:?}");
+        assert!(leaks[0].starts_with("interval "), "{leaks:?}");
+    }
+
+    #[wasm_bindgen_test]
+    async fn ignores_the_library_listeners() {
+        let wrapper = mount_test(|| view! { <p>"quiet"</p> }).with_console_capture();
+
+        assert_eq!(wrapper.unmount().await.leaks(), Vec::<String>::new());
+    }
+
+    #[wasm_bindgen_test]
+    async fn ignores_other_mounts() {
+        let wrapper = mount_test(|| view! { <p>"quiet"</p> });
+        let _other = mount_test(|| {
+            let handle = window_event_listener(ev::resize, |_| {});
+            on_cleanup(move || handle.remove());
+
+            view! { <p>"listening"</p> }
+        });
+
+        assert_eq!(wrapper.unmount().await.leaks(), Vec::<String>::new());
+    }
+
+    #[wasm_bindgen_test]
+    async fn tells_targets_apart() {
+        let wrapper = mount_test(|| view! { <p>"quiet"</p> });
+        let window = web_sys::window().unwrap();
+        let document = window.document().unwrap();
+        let callback = Closure::<dyn Fn()>::new(|| {}).into_js_value();
+
+        window
+            .add_event_listener_with_callback("scroll", callback.unchecked_ref())
+            .unwrap();
+        document
+            .add_event_listener_with_callback("scroll", callback.unchecked_ref())
+            .unwrap();
+        document
+            .remove_event_listener_with_callback("scroll", callback.unchecked_ref())
+            .unwrap();
+
+        let leaks = wrapper.unmount().await.leaks();
+        window
+            .remove_event_listener_with_callback("scroll", callback.unchecked_ref())
+            .unwrap();
+
+        assert_eq!(leaks, vec!["`scroll` listener on window".to_string()]);
+    }
+}
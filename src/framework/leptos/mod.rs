@@ -1,5 +1,21 @@
+pub mod action;
+#[cfg(feature = "leptos-hydrate")]
+pub mod hydrate;
 mod implementation;
+mod leaks;
 pub mod mount;
+pub mod props;
+#[cfg(feature = "leptos-router")]
+pub mod router;
+pub mod server_fn;
+mod suspense;
 
-pub use implementation::Leptos;
+pub use action::TestAction;
+#[cfg(feature = "leptos-hydrate")]
+pub use hydrate::*;
+pub use implementation::{Leptos, MountedView};
 pub use mount::*;
+pub use props::*;
+#[cfg(feature = "leptos-router")]
+pub use router::*;
+pub use server_fn::{ServerFnMock, assert_server_fn_called, mock_server_fn};
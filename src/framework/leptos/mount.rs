@@ -1,7 +1,12 @@
-use std::sync::Arc;
+use std::rc::Rc;
 
-use super::implementation::Leptos;
-use crate::{framework::leptos::implementation::ErasedDestructor, wrapper::BaseTestWrapper};
+use wasm_bindgen::JsCast as _;
+
+use super::{
+    implementation::{Leptos, MountedView},
+    leaks, suspense,
+};
+use crate::{isolation::TestRoot, wrapper::BaseTestWrapper};
 use leptos::prelude::*;
 
 /// Mounts a view into a new element on the dom and returns a [`BaseTestWrapper`] for working with it
@@ -11,18 +16,91 @@ where
     V: IntoView,
     <V as Render>::State: 'static,
 {
-    use wasm_bindgen::JsCast as _;
+    mount_test_with(|cx| cx, f).0
+}
+
+/// Mounts a view like [`mount_test`], but with some contexts provided to it first
+///
+/// ```ignore
+/// let (wrapper, contexts) = mount_test_with(
+///     |cx| cx.provide(MockApi::new()).provide(Theme::Dark),
+///     || view! { <Settings /> },
+/// );
+/// ```
+///
+/// The returned [`TestContexts`] can be used to read the contexts back, e.g. to check what the view
+/// did with them after some interaction
+pub fn mount_test_with<C, F, V>(contexts: C, f: F) -> (BaseTestWrapper<Leptos>, TestContexts)
+where
+    C: FnOnce(ContextBuilder) -> ContextBuilder,
+    F: FnOnce() -> V + 'static,
+    V: IntoView,
+    <V as Render>::State: 'static,
+{
+    suspense::install_tracker();
+    let leak_checkpoint = leaks::checkpoint();
 
-    let document = document();
-    let test_root_node = document.create_element("section").unwrap();
-    let _ = document.body().unwrap().append_child(&test_root_node);
+    let owner = Owner::new();
+    let test_root = TestRoot::new();
+    let test_root_node = test_root.element().clone();
+    let handle = owner.with(|| {
+        suspense::provide_root_context();
+        for provide in contexts(ContextBuilder::default()).providers {
+            provide();
+        }
 
-    let handle = mount_to(test_root_node.clone().unchecked_into(), f);
+        mount_to(test_root_node.clone().unchecked_into(), f)
+    });
 
-    BaseTestWrapper::with_root(
+    let wrapper = BaseTestWrapper::with_root(
         test_root_node,
-        Arc::new(handle) as Arc<dyn ErasedDestructor>,
-    )
+        // the owner must outlive the mounted view, since it's its parent
+        Rc::new(MountedView::new(
+            handle,
+            owner.clone(),
+            test_root,
+            leak_checkpoint,
+        )),
+    );
+
+    (wrapper, TestContexts { owner })
+}
+
+/// Collects the contexts to provide to a view mounted with [`mount_test_with`]
+#[derive(Default)]
+pub struct ContextBuilder {
+    providers: Vec<Box<dyn FnOnce()>>,
+}
+
+impl ContextBuilder {
+    /// Provides a context to the mounted view, as with [`provide_context`]
+    pub fn provide<T: Send + Sync + 'static>(mut self, value: T) -> Self {
+        self.providers
+            .push(Box::new(move || provide_context(value)));
+        self
+    }
+}
+
+/// A handle to the contexts provided to a view mounted with [`mount_test_with`]
+pub struct TestContexts {
+    owner: Owner,
+}
+
+impl TestContexts {
+    /// Reads a provided context, as with [`use_context`]
+    pub fn get<T: Clone + 'static>(&self) -> Option<T> {
+        self.owner.with(use_context)
+    }
+
+    /// Reads a provided context, panics if it wasn't provided
+    pub fn expect<T: Clone + 'static>(&self) -> T {
+        self.get().unwrap_or_else(|| {
+            panic!(
+                "context of type `{}` was not provided",
+                std::any::type_name::<T>()
+            )
+        })
+    }
 }
 
 #[cfg(test)]
@@ -42,4 +120,45 @@ mod test {
 
         test_wrapper.query("#mounted-span").assert_exists();
     }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    #[cfg_attr(not(target_family = "wasm"), ignore)]
+    fn provides_contexts() {
+        #[derive(Clone)]
+        struct Greeting(&'static str);
+
+        let (test_wrapper, contexts) = mount_test_with(
+            |cx| cx.provide(Greeting("hello")).provide(RwSignal::new(0)),
+            || {
+                let greeting = expect_context::<Greeting>();
+                view! { <span id="greeting">{greeting.0}</span> }
+            },
+        );
+
+        test_wrapper
+            .query("#greeting")
+            .assert_exists()
+            .assert_text_is("hello");
+        assert_eq!(contexts.expect::<Greeting>().0, "hello");
+    }
+
+    #[cfg(target_family = "wasm")]
+    #[wasm_bindgen_test]
+    async fn reads_contexts_after_interactions() {
+        let (test_wrapper, contexts) = mount_test_with(
+            |cx| cx.provide(RwSignal::new(0)),
+            || {
+                let count = expect_context::<RwSignal<i32>>();
+                view! { <button on:click=move |_| count.update(|count| *count += 1)>increment</button> }
+            },
+        );
+
+        test_wrapper
+            .query_as_button("button")
+            .assert_exists()
+            .click()
+            .await;
+
+        assert_eq!(contexts.expect::<RwSignal<i32>>().get_untracked(), 1);
+    }
 }
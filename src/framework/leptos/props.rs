@@ -0,0 +1,142 @@
+use leptos::prelude::*;
+
+use super::{
+    implementation::{Leptos, SettleFn},
+    mount::mount_test,
+};
+use crate::wrapper::BaseTestWrapper;
+
+/// Mounts a view that gets its props from a signal, which can be changed later on through the
+/// returned [`PropsHandle`]
+///
+/// ```ignore
+/// let (wrapper, props) = mount_test_with_props(Greeting { name: "Ann" }, |props| view! {
+///     <Greeter name=move || props.get().name />
+/// });
+/// props.set(Greeting { name: "Bob" }).await;
+/// wrapper.query("p").assert_exists().assert_text_is("Hello, Bob");
+/// ```
+pub fn mount_test_with_props<P, F, V>(
+    initial_props: P,
+    f: F,
+) -> (BaseTestWrapper<Leptos>, PropsHandle<P>)
+where
+    P: Send + Sync + 'static,
+    F: FnOnce(ReadSignal<P>) -> V + 'static,
+    V: IntoView,
+    <V as Render>::State: 'static,
+{
+    let props = ArcRwSignal::new(initial_props);
+    let props_clone = props.clone();
+    let wrapper = mount_test(move || f(props_clone.read_only().into()));
+
+    let handle = PropsHandle {
+        settle: wrapper.settle_fn(),
+        props,
+    };
+
+    (wrapper, handle)
+}
+
+/// A handle to the props of a view mounted with [`mount_test_with_props`]
+pub struct PropsHandle<P> {
+    /// Settles like the wrapper mounted alongside
+    settle: SettleFn,
+    props: ArcRwSignal<P>,
+}
+
+impl<P: Send + Sync + 'static> PropsHandle<P> {
+    /// Replaces the props, like rerendering the view with new ones, and waits for the view to update
+    pub async fn set(&self, new_props: P) {
+        self.props.set(new_props);
+        (self.settle)().await;
+    }
+
+    /// Changes the props in place and waits for the view to update
+    pub async fn update(&self, f: impl FnOnce(&mut P)) {
+        self.props.update(f);
+        (self.settle)().await;
+    }
+
+    /// The current props
+    pub fn get(&self) -> P
+    where
+        P: Clone,
+    {
+        self.props.get_untracked()
+    }
+}
+
+#[cfg(all(test, target_family = "wasm"))]
+mod tests {
+    use super::*;
+    use leptos::view;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Clone)]
+    struct Greeting {
+        name: &'static str,
+        excited: bool,
+    }
+
+    #[component]
+    fn Greeter(
+        #[prop(into)] name: Signal<&'static str>,
+        #[prop(into)] excited: Signal<bool>,
+    ) -> impl IntoView {
+        view! { <p>"Hello, " {name} {move || excited.get().then_some("!")}</p> }
+    }
+
+    fn mount_greeter() -> (BaseTestWrapper<Leptos>, PropsHandle<Greeting>) {
+        mount_test_with_props(
+            Greeting {
+                name: "Ann",
+                excited: false,
+            },
+            |props| {
+                view! {
+                    <Greeter
+                        name=move || props.get().name
+                        excited=move || props.get().excited
+                    />
+                }
+            },
+        )
+    }
+
+    #[wasm_bindgen_test]
+    async fn rerenders_with_new_props() {
+        let (wrapper, props) = mount_greeter();
+        wrapper
+            .query("p")
+            .assert_exists()
+            .assert_text_is("Hello, Ann");
+
+        props
+            .set(Greeting {
+                name: "Bob",
+                excited: true,
+            })
+            .await;
+
+        wrapper
+            .query("p")
+            .assert_exists()
+            .assert_text_is("Hello, Bob!");
+    }
+
+    #[wasm_bindgen_test]
+    async fn updates_props_in_place() {
+        let (wrapper, props) = mount_greeter();
+
+        props.update(|props| props.excited = true).await;
+
+        wrapper
+            .query("p")
+            .assert_exists()
+            .assert_text_is("Hello, Ann!");
+        assert_eq!(props.get().name, "Ann");
+    }
+}
@@ -0,0 +1,380 @@
+use std::sync::{Arc, Mutex};
+
+use leptos::prelude::*;
+use leptos_router::{
+    NavigateOptions,
+    components::Router,
+    hooks::{use_location, use_navigate},
+    location::Location,
+};
+use wasm_bindgen::{JsCast as _, JsValue};
+
+use super::{
+    implementation::{Leptos, SettleFn},
+    mount::mount_test,
+};
+use crate::{framework::SettleStrategy, wrapper::BaseTestWrapper};
+
+/// Shadows the history methods with ones that keep the entries in memory: pushing replaces the
+/// current entry of the real history, and traversing replaces it with the target entry and fires
+/// `popstate` right away. Returns a handle to inspect and remove it
+///
+/// A router that was never dropped (as after a panic, since tests run with `panic=abort`) leaves its
+/// overrides behind, so they're taken over instead of being built on
+const INSTALL_HISTORY: &str = r#"
+const names = ["pushState", "replaceState", "back", "forward", "go", "length"];
+for (const name of names) {
+    delete history[name];
+}
+
+const replaceState = History.prototype.replaceState;
+const replace = (state, url) => replaceState.call(history, state, "", url);
+const entries = [{ state: history.state, url: location.href }];
+let index = 0;
+
+const go = (delta) => {
+    const target = index + (delta ?? 0);
+    if (target === index || target < 0 || target >= entries.length) return;
+    index = target;
+    replace(entries[index].state, entries[index].url);
+    window.dispatchEvent(new PopStateEvent("popstate", { state: entries[index].state }));
+};
+const overrides = {
+    pushState(state, _title, url) {
+        replace(state, url);
+        entries.splice(index + 1, Infinity, { state, url: location.href });
+        index += 1;
+    },
+    replaceState(state, _title, url) {
+        replace(state, url);
+        entries[index] = { state, url: location.href };
+    },
+    back() {
+        go(-1);
+    },
+    forward() {
+        go(1);
+    },
+    go,
+};
+
+for (const [name, value] of Object.entries(overrides)) {
+    Object.defineProperty(history, name, { value, configurable: true, writable: true });
+}
+Object.defineProperty(history, "length", { get: () => entries.length, configurable: true });
+
+return {
+    index: () => index,
+    restore: (state, url) => {
+        // a newer router took over, the history is its own now
+        if (history.pushState !== overrides.pushState) return;
+
+        for (const name of names) {
+            delete history[name];
+        }
+        replace(state, url);
+    },
+};
+"#;
+
+/// Mounts a view inside of a [`Router`] that starts at `initial_path`
+///
+/// ```ignore
+/// let (wrapper, router) = mount_test_with_router("/items?page=2", || view! {
+///     <Routes fallback=|| "not found">
+///         <Route path=path!("/items") view=Items />
+///     </Routes>
+/// });
+/// router.navigate("/items/1").await;
+/// router.assert_path("/items/1");
+/// ```
+///
+/// The router reads the browser's URL, but navigations go to a history owned by the returned
+/// [`TestRouter`] instead of the session history, and the original URL is put back once it's
+/// dropped. Only one can be used at a time: mounting another one takes the history over from the
+/// previous one, which leaves it alone when it's dropped
+///
+/// Navigation may involve loading routes, so the wrapper settles with
+/// [`SettleStrategy::Quiescent`], as does the router. Both can be changed with
+/// [`with_settle_strategy`](crate::BaseTestWrapper::with_settle_strategy)
+pub fn mount_test_with_router<F, V>(
+    initial_path: &str,
+    f: F,
+) -> (BaseTestWrapper<Leptos>, TestRouter)
+where
+    F: FnOnce() -> V + Send + 'static,
+    V: IntoView + 'static,
+{
+    let window = window();
+    let original_url = window.location().href().unwrap();
+    let history = window.history().unwrap();
+    let original_state = history.state().unwrap();
+    history
+        .replace_state_with_url(&JsValue::NULL, "", Some(initial_path))
+        .unwrap();
+    let handle = js_sys::Function::new_no_args(INSTALL_HISTORY)
+        .call0(&JsValue::NULL)
+        .unwrap();
+
+    let probe = Arc::new(Mutex::new(None));
+    let probe_clone = Arc::clone(&probe);
+    let wrapper = mount_test(move || {
+        view! {
+            <Router>
+                {
+                    *probe_clone.lock().unwrap() = Some((
+                        Owner::current().expect("no owner inside of the router"),
+                        use_location(),
+                    ));
+                    f()
+                }
+            </Router>
+        }
+    });
+
+    let (owner, location) = probe
+        .lock()
+        .unwrap()
+        .take()
+        .expect("router children were not rendered");
+
+    let wrapper = wrapper.with_settle_strategy(SettleStrategy::Quiescent);
+    let router = TestRouter {
+        settle: wrapper.settle_fn(),
+        owner,
+        location,
+        history: handle,
+        original_url,
+        original_state,
+    };
+
+    (wrapper, router)
+}
+
+/// A handle to the router of a view mounted with [`mount_test_with_router`]
+pub struct TestRouter {
+    /// Settles like the wrapper mounted alongside
+    settle: SettleFn,
+    owner: Owner,
+    location: Location,
+    /// The in-memory history, see [`INSTALL_HISTORY`]
+    history: JsValue,
+    original_url: String,
+    original_state: JsValue,
+}
+
+impl TestRouter {
+    /// Navigates to the given path (which may include a query string) as a `<A>` would
+    pub async fn navigate(&self, path: &str) {
+        self.owner
+            .with(|| use_navigate()(path, NavigateOptions::default()));
+        (self.settle)().await;
+    }
+
+    /// Goes back in the history
+    ///
+    /// panics if there's no entry to go back to
+    pub async fn back(&self) {
+        self.traverse(-1).await;
+    }
+
+    /// Goes forward in the history
+    ///
+    /// panics if there's no entry to go forward to
+    pub async fn forward(&self) {
+        self.traverse(1).await;
+    }
+
+    /// The current path as seen by the router, without the query string or hash
+    pub fn current_path(&self) -> String {
+        self.location.pathname.get_untracked()
+    }
+
+    /// The current query string as seen by the router, without the leading `?`
+    pub fn current_search(&self) -> String {
+        self.location.search.get_untracked()
+    }
+
+    /// The value of a query parameter, as seen by the router
+    pub fn query_param(&self, key: &str) -> Option<String> {
+        self.location.query.with_untracked(|query| query.get(key))
+    }
+
+    pub fn assert_path(&self, expected: &str) -> &Self {
+        assert_eq!(
+            self.current_path(),
+            expected,
+            "router is not at the expected path"
+        );
+        self
+    }
+
+    pub fn assert_query_param(&self, key: &str, expected: &str) -> &Self {
+        assert_eq!(
+            self.query_param(key).as_deref(),
+            Some(expected),
+            "unexpected value for query parameter `{key}`"
+        );
+        self
+    }
+
+    pub fn assert_no_query_param(&self, key: &str) -> &Self {
+        let value = self.query_param(key);
+        assert!(
+            value.is_none(),
+            "query parameter `{key}` is present with value `{}`",
+            value.unwrap_or_default()
+        );
+        self
+    }
+
+    /// The position of the current entry in the history
+    fn index(&self) -> f64 {
+        js_sys::Reflect::get(&self.history, &"index".into())
+            .unwrap()
+            .unchecked_into::<js_sys::Function>()
+            .call0(&JsValue::NULL)
+            .unwrap()
+            .as_f64()
+            .unwrap()
+    }
+
+    async fn traverse(&self, delta: i32) {
+        let before = self.index();
+        window().history().unwrap().go_with_delta(delta).unwrap();
+        assert_ne!(
+            self.index(),
+            before,
+            "there's no history entry to go {}",
+            if delta < 0 { "back to" } else { "forward to" }
+        );
+
+        (self.settle)().await;
+    }
+}
+
+impl Drop for TestRouter {
+    fn drop(&mut self) {
+        js_sys::Reflect::get(&self.history, &"restore".into())
+            .unwrap()
+            .unchecked_into::<js_sys::Function>()
+            .call2(
+                &JsValue::NULL,
+                &self.original_state,
+                &self.original_url.as_str().into(),
+            )
+            .unwrap();
+    }
+}
+
+#[cfg(all(test, target_family = "wasm"))]
+mod tests {
+    use leptos_router::{
+        components::{A, Route, Routes},
+        path,
+    };
+    use wasm_bindgen_test::*;
+
+    use super::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn routes() -> impl IntoView {
+        view! {
+            <nav>
+                <A href="/about">About</A>
+            </nav>
+            <Routes fallback=|| view! { <p id="not-found">Not found</p> }>
+                <Route path=path!("/") view=|| view! { <h1>Home</h1> } />
+                <Route path=path!("/about") view=|| view! { <h1>About</h1> } />
+                <Route path=path!("/items") view=|| view! { <h1>Items</h1> } />
+            </Routes>
+        }
+    }
+
+    #[wasm_bindgen_test]
+    async fn starts_at_initial_path() {
+        let (wrapper, router) = mount_test_with_router("/items?page=2", routes);
+
+        router.assert_path("/items").assert_query_param("page", "2");
+        wrapper.query("h1").assert_exists().assert_text_is("Items");
+    }
+
+    #[wasm_bindgen_test]
+    async fn navigates() {
+        let (wrapper, router) = mount_test_with_router("/", routes);
+
+        router.navigate("/items?page=3").await;
+
+        router.assert_path("/items").assert_query_param("page", "3");
+        wrapper.query("h1").assert_exists().assert_text_is("Items");
+    }
+
+    #[wasm_bindgen_test]
+    async fn goes_back_and_forward() {
+        let (wrapper, router) = mount_test_with_router("/", routes);
+
+        wrapper
+            .query_as::<web_sys::HtmlElement>("a")
+            .assert_exists()
+            .click()
+            .await;
+        router.assert_path("/about");
+
+        router.back().await;
+        router.assert_path("/").assert_no_query_param("page");
+        wrapper.query("h1").assert_exists().assert_text_is("Home");
+
+        router.forward().await;
+        router.assert_path("/about");
+    }
+
+    #[wasm_bindgen_test]
+    async fn keeps_the_session_history() {
+        let history = window().history().unwrap();
+        let length = history.length().unwrap();
+        let url = window().location().href().unwrap();
+
+        let (wrapper, router) = mount_test_with_router("/", routes);
+        router.navigate("/about").await;
+        router.navigate("/items").await;
+        assert_eq!(history.length().unwrap(), 3);
+        drop(router);
+        drop(wrapper);
+
+        assert_eq!(history.length().unwrap(), length);
+        assert_eq!(window().location().href().unwrap(), url);
+    }
+
+    #[wasm_bindgen_test]
+    async fn takes_over_a_leftover_history() {
+        let url = window().location().href().unwrap();
+
+        // as if its test had panicked
+        let (wrapper, router) = mount_test_with_router("/about", routes);
+        std::mem::forget(router);
+        drop(wrapper);
+
+        let (wrapper, router) = mount_test_with_router("/", routes);
+        router.navigate("/items").await;
+        router.back().await;
+        router.assert_path("/");
+        assert_eq!(window().history().unwrap().length().unwrap(), 2);
+        drop(router);
+        drop(wrapper);
+
+        window()
+            .history()
+            .unwrap()
+            .replace_state_with_url(&JsValue::NULL, "", Some(&url))
+            .unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    #[should_panic = "there's no history entry to go forward to"]
+    async fn forward_panics_without_entry() {
+        let (_wrapper, router) = mount_test_with_router("/", routes);
+
+        router.forward().await;
+    }
+}
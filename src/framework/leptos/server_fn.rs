@@ -0,0 +1,349 @@
+//! Mocks for `#[server]` functions
+//!
+//! Server functions call `fetch` on the client, so while there is any mock alive a layer of `fetch`
+//! (see [`crate::network`]) answers the requests to mocked server functions in-page and forwards
+//! everything else. Only the default encodings are supported: URL-encoded or JSON arguments and
+//! JSON output.
+
+use std::{
+    cell::RefCell, collections::HashMap, future::Future, marker::PhantomData, pin::Pin, rc::Rc,
+};
+
+use leptos::server_fn::{ServerFn, error::FromServerFnError};
+use serde::{Serialize, de::DeserializeOwned};
+use wasm_bindgen::{JsCast as _, JsValue};
+
+use crate::network::{self, FetchLayerGuard, Next};
+
+/// Answers a request to a mocked server function with a status and a body
+type Handler = Rc<dyn Fn(MockRequest) -> Pin<Box<dyn Future<Output = (u16, String)>>>>;
+
+thread_local! {
+    static REGISTRY: RefCell<Registry> = RefCell::default();
+}
+
+#[derive(Default)]
+struct Registry {
+    next_id: usize,
+    /// The mocks alive for each server function, the most recent one answers
+    handlers: HashMap<&'static str, Vec<(usize, Handler)>>,
+    /// The arguments of every call to a mocked server function, serialized as JSON
+    calls: HashMap<&'static str, Vec<serde_json::Value>>,
+    /// Present while any mock is alive
+    fetch: Option<FetchLayerGuard>,
+}
+
+/// The parts of a request needed to decode the arguments of a server function
+struct MockRequest {
+    method: String,
+    query: String,
+    content_type: String,
+    accept: String,
+    body: String,
+}
+
+/// Answers every call to the server function `F` with `handler` instead of sending a request, for
+/// as long as the returned [`ServerFnMock`] is alive
+///
+/// ```ignore
+/// let _get_user = mock_server_fn::<GetUser>(|args| async move {
+///     Ok(User { id: args.id, name: "Ann".to_string() })
+/// });
+/// let wrapper = mount_test(|| view! { <UserProfile id=1 /> });
+/// ```
+///
+/// Mocking a server function that's already mocked overrides the previous mock until the new one is
+/// dropped. The calls are recorded until every mock of `F` is dropped
+pub fn mock_server_fn<F>(
+    handler: impl AsyncFn(F) -> Result<F::Output, F::Error> + 'static,
+) -> ServerFnMock<F>
+where
+    F: ServerFn + Serialize + DeserializeOwned + 'static,
+    F::Output: Serialize,
+{
+    let handler = Rc::new(handler);
+    let handler: Handler = Rc::new(move |request| {
+        let handler = Rc::clone(&handler);
+        Box::pin(async move {
+            let args = decode_args::<F>(&request);
+            REGISTRY.with_borrow_mut(|registry| {
+                registry
+                    .calls
+                    .entry(F::PATH)
+                    .or_default()
+                    .push(serde_json::to_value(&args).unwrap());
+            });
+
+            match handler(args).await {
+                Ok(output) => (200, serde_json::to_string(&output).unwrap()),
+                Err(err) => (500, String::from_utf8_lossy(&err.ser()).into_owned()),
+            }
+        })
+    });
+
+    let needs_fetch = REGISTRY.with_borrow(|registry| registry.fetch.is_none());
+    let fetch = needs_fetch.then(|| network::push_fetch_layer(Rc::new(fetch_layer)));
+
+    let id = REGISTRY.with_borrow_mut(|registry| {
+        let id = registry.next_id;
+        registry.next_id += 1;
+        registry
+            .handlers
+            .entry(F::PATH)
+            .or_default()
+            .push((id, handler));
+        registry.calls.entry(F::PATH).or_default();
+        if fetch.is_some() {
+            registry.fetch = fetch;
+        }
+        id
+    });
+
+    ServerFnMock {
+        id,
+        path: F::PATH,
+        _server_fn: PhantomData,
+    }
+}
+
+/// Keeps a mock of the server function `F` registered until it's dropped, see [`mock_server_fn`]
+#[must_use = "the mock is removed as soon as this is dropped"]
+pub struct ServerFnMock<F> {
+    id: usize,
+    path: &'static str,
+    _server_fn: PhantomData<fn(F)>,
+}
+
+impl<F: ServerFn + Serialize> ServerFnMock<F> {
+    /// How many times `F` was called while mocked
+    pub fn call_count(&self) -> usize {
+        REGISTRY.with_borrow(|registry| registry.calls.get(self.path).map_or(0, Vec::len))
+    }
+
+    /// See [`assert_server_fn_called`]
+    pub fn assert_called(&self, times: usize, with_args: Option<F>) -> &Self {
+        assert_server_fn_called::<F>(times, with_args);
+        self
+    }
+}
+
+impl<F> Drop for ServerFnMock<F> {
+    fn drop(&mut self) {
+        let fetch = REGISTRY.with_borrow_mut(|registry| {
+            if let Some(handlers) = registry.handlers.get_mut(self.path) {
+                handlers.retain(|(id, _)| *id != self.id);
+                if handlers.is_empty() {
+                    registry.handlers.remove(self.path);
+                    registry.calls.remove(self.path);
+                }
+            }
+
+            if registry.handlers.is_empty() {
+                registry.fetch.take()
+            } else {
+                None
+            }
+        });
+
+        // removing the layer touches the registry of the network mocks, so it's done outside of
+        // this one's borrow
+        drop(fetch);
+    }
+}
+
+/// Asserts that the mocked server function `F` was called `times` times, and if `with_args` is
+/// given, that one of the calls was made with those arguments
+pub fn assert_server_fn_called<F>(times: usize, with_args: Option<F>)
+where
+    F: ServerFn + Serialize,
+{
+    let calls = REGISTRY.with_borrow(|registry| registry.calls.get(F::PATH).cloned());
+    let calls =
+        calls.unwrap_or_else(|| panic!("server fn `{}` is not mocked", std::any::type_name::<F>()));
+
+    assert_eq!(
+        calls.len(),
+        times,
+        "server fn `{}` was called an unexpected number of times, calls: {calls:?}",
+        std::any::type_name::<F>()
+    );
+
+    if let Some(args) = with_args {
+        let args = serde_json::to_value(&args).unwrap();
+        assert!(
+            calls.contains(&args),
+            "server fn `{}` was never called with {args}, calls: {calls:?}",
+            std::any::type_name::<F>()
+        );
+    }
+}
+
+/// Answers the requests to mocked server functions and passes on the rest
+fn fetch_layer(input: JsValue, init: JsValue, next: Next) -> js_sys::Promise {
+    let request = if input.is_instance_of::<web_sys::Request>() {
+        input.clone().unchecked_into()
+    } else {
+        match web_sys::Request::new_with_str_and_init(
+            &input.as_string().unwrap_or_default(),
+            init.unchecked_ref(),
+        ) {
+            Ok(request) => request,
+            Err(_) => return next.fetch(input, init),
+        }
+    };
+
+    match find_handler(&request) {
+        Some(handler) => wasm_bindgen_futures::future_to_promise(respond(handler, request)),
+        None => next.fetch(input, init),
+    }
+}
+
+fn find_handler(request: &web_sys::Request) -> Option<Handler> {
+    let path = web_sys::Url::new(&request.url()).ok()?.pathname();
+    REGISTRY.with_borrow(|registry| {
+        let (_, handler) = registry.handlers.get(path.as_str())?.last()?;
+        Some(Rc::clone(handler))
+    })
+}
+
+async fn respond(handler: Handler, request: web_sys::Request) -> Result<JsValue, JsValue> {
+    let url = web_sys::Url::new(&request.url())?;
+    let headers = request.headers();
+    let body = wasm_bindgen_futures::JsFuture::from(request.text()?).await?;
+    let request = MockRequest {
+        method: request.method(),
+        query: url.search(),
+        content_type: headers.get("content-type")?.unwrap_or_default(),
+        accept: headers.get("accept")?.unwrap_or_default(),
+        body: body.as_string().unwrap_or_default(),
+    };
+
+    assert!(
+        request.accept.contains("application/json"),
+        "mocked server fns must return JSON, but `{}` was requested",
+        request.accept
+    );
+
+    let (status, body) = handler(request).await;
+
+    let init = web_sys::ResponseInit::new();
+    init.set_status(status);
+    let response_headers = web_sys::Headers::new()?;
+    response_headers.set("content-type", "application/json")?;
+    init.set_headers(&response_headers);
+
+    web_sys::Response::new_with_opt_str_and_init(Some(&body), &init).map(Into::into)
+}
+
+fn decode_args<F: ServerFn + DeserializeOwned>(request: &MockRequest) -> F {
+    let args = if request.method == "GET" {
+        serde_qs::from_str(request.query.trim_start_matches('?')).map_err(|err| err.to_string())
+    } else if request
+        .content_type
+        .starts_with("application/x-www-form-urlencoded")
+    {
+        serde_qs::from_str(&request.body).map_err(|err| err.to_string())
+    } else if request.content_type.starts_with("application/json") {
+        serde_json::from_str(&request.body).map_err(|err| err.to_string())
+    } else {
+        Err(format!(
+            "unsupported content type `{}`",
+            request.content_type
+        ))
+    };
+
+    args.unwrap_or_else(|err| {
+        panic!(
+            "can't decode the arguments of server fn `{}`: {err}",
+            std::any::type_name::<F>()
+        )
+    })
+}
+
+#[cfg(all(test, target_family = "wasm"))]
+mod tests {
+    use super::*;
+    use crate::framework::leptos::mount_test;
+    use leptos::{prelude::*, view};
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[server]
+    async fn get_greeting(name: String) -> Result<String, ServerFnError> {
+        unreachable!("only called through mocks in tests")
+    }
+
+    fn greeter(name: &'static str) -> impl IntoView {
+        let greeting = LocalResource::new(move || get_greeting(name.to_string()));
+        view! {
+            <Suspense>
+                <p>
+                    {move || {
+                        greeting
+                            .get()
+                            .map(|greeting| greeting.unwrap_or_else(|err| err.to_string()))
+                    }}
+                </p>
+            </Suspense>
+        }
+    }
+
+    #[wasm_bindgen_test]
+    async fn answers_calls_with_the_mock() {
+        let get_greeting =
+            mock_server_fn::<GetGreeting>(async |args| Ok(format!("Hello, {}", args.name)));
+
+        let wrapper = mount_test(|| greeter("Ann"));
+        wrapper.wait_for_suspense().await;
+
+        wrapper
+            .query("p")
+            .assert_exists()
+            .assert_text_is("Hello, Ann");
+        get_greeting.assert_called(
+            1,
+            Some(GetGreeting {
+                name: "Ann".to_string(),
+            }),
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn returns_errors_from_the_mock() {
+        let _get_greeting = mock_server_fn::<GetGreeting>(async |_| {
+            Err(ServerFnError::ServerError("no greetings today".to_string()))
+        });
+
+        let wrapper = mount_test(|| greeter("Ann"));
+        wrapper.wait_for_suspense().await;
+
+        wrapper
+            .query("p")
+            .assert_exists()
+            .assert_text_contains("no greetings today");
+    }
+
+    #[wasm_bindgen_test]
+    async fn removes_mocks_on_drop() {
+        let first = mock_server_fn::<GetGreeting>(async |_| Ok("first".to_string()));
+        let second = mock_server_fn::<GetGreeting>(async |_| Ok("second".to_string()));
+
+        let wrapper = mount_test(|| greeter("Ann"));
+        wrapper.wait_for_suspense().await;
+        wrapper.query("p").assert_exists().assert_text_is("second");
+
+        drop(second);
+        let wrapper = mount_test(|| greeter("Ann"));
+        wrapper.wait_for_suspense().await;
+        wrapper.query("p").assert_exists().assert_text_is("first");
+        first.assert_called(2, None);
+
+        drop(first);
+        REGISTRY.with_borrow(|registry| {
+            assert!(registry.handlers.is_empty());
+            assert!(registry.calls.is_empty());
+            assert!(registry.fetch.is_none());
+        });
+    }
+}
@@ -0,0 +1,445 @@
+//! Waiting for `<Suspense>` and `<Transition>` boundaries to resolve
+//!
+//! Leptos registers every pending read of a resource (or a `Suspend`) in the closest
+//! [`SuspenseContext`] and spawns a task that unregisters it once the data is there. The executor
+//! installed here notes down the context of every task spawned that way, so a test can wait until
+//! all of them are empty again. Each read is noted down along with where it happened, which is
+//! taken from the JS stack and so needs the wasm to keep its function names (as debug builds do).
+
+use std::{cell::RefCell, sync::OnceLock, time::Duration};
+
+use any_spawner::{CustomExecutor, Executor, PinnedFuture, PinnedLocalFuture};
+use leptos::{prelude::*, reactive::computed::suspense::SuspenseContext};
+use slotmap::{DefaultKey, SlotMap};
+
+use super::implementation::Leptos;
+use crate::{
+    task, timers,
+    wrapper::{TestWrapper, TestWrapperState},
+};
+
+/// How long [`TestWrapper::wait_for_suspense`] and [`TestWrapper::wait_for_transition`] wait
+const SUSPENSE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Frames of the stack of a pending read that are about spawning it rather than reading it
+const INTERNAL_FRAMES: &[&str] = &[
+    "dom_test_lib::framework::leptos::suspense::read_site",
+    "dom_test_lib::framework::leptos::suspense::track_current_context",
+    "dom_test_lib::framework::leptos::suspense::TrackingExecutor",
+    "any_spawner::",
+    "reactive_graph::",
+    "leptos_server::",
+    "leptos::",
+    "tachys::",
+    "core::",
+    "std::",
+    "alloc::",
+    "wasm_bindgen",
+    "js_sys::",
+];
+
+static TRACKER_INSTALLED: OnceLock<bool> = OnceLock::new();
+
+thread_local! {
+    static TRACKED: RefCell<Vec<TrackedContext>> = const { RefCell::new(Vec::new()) };
+    static READS: RefCell<Vec<PendingRead>> = const { RefCell::new(Vec::new()) };
+}
+
+type Tasks = ArcRwSignal<SlotMap<DefaultKey, ()>>;
+
+/// A [`SuspenseContext`] that had some pending reads when a task was spawned under it
+struct TrackedContext {
+    context: SuspenseContext,
+    /// [`Owner::debug_id`] of the owner that spawned the task, followed by the ones of its ancestors
+    ancestry: Vec<usize>,
+}
+
+/// A read registered in the tasks of a [`SuspenseContext`]
+struct PendingRead {
+    tasks: Tasks,
+    key: DefaultKey,
+    /// The function that read, if it could be told
+    site: Option<String>,
+}
+
+impl PendingRead {
+    fn is_pending(&self) -> bool {
+        self.tasks
+            .try_with_untracked(|tasks| tasks.contains_key(self.key))
+            .unwrap_or(false)
+    }
+}
+
+/// Spawns tasks the same way `wasm-bindgen-futures` does, but notes down their suspense context
+struct TrackingExecutor;
+
+impl CustomExecutor for TrackingExecutor {
+    fn spawn(&self, fut: PinnedFuture<()>) {
+        self.spawn_local(fut);
+    }
+
+    fn spawn_local(&self, fut: PinnedLocalFuture<()>) {
+        track_current_context();
+        wasm_bindgen_futures::spawn_local(fut);
+    }
+
+    fn poll_local(&self) {}
+}
+
+/// Sets up the Leptos executor so pending reads can be tracked, must be done before anything is
+/// mounted since the executor can only be set once
+pub(super) fn install_tracker() {
+    TRACKER_INSTALLED.get_or_init(|| Executor::init_custom_executor(TrackingExecutor).is_ok());
+}
+
+/// Provides a [`SuspenseContext`] for the reads that aren't under any `<Suspense>`/`<Transition>`
+pub(super) fn provide_root_context() {
+    provide_context(SuspenseContext {
+        tasks: Default::default(),
+    });
+}
+
+fn track_current_context() {
+    let Some(owner) = Owner::current() else {
+        return;
+    };
+    let Some(context) = use_context::<SuspenseContext>() else {
+        return;
+    };
+    if !has_pending_reads(&context) {
+        return;
+    }
+
+    track_reads(&context.tasks);
+
+    TRACKED.with_borrow_mut(|tracked| {
+        if tracked.iter().any(|t| t.context.tasks == context.tasks) {
+            return;
+        }
+
+        let mut ancestry = vec![owner.debug_id()];
+        ancestry.extend(owner.ancestry());
+        tracked.push(TrackedContext { context, ancestry });
+    });
+}
+
+/// Notes down where the reads registered since the last spawn happened. A read registers itself
+/// right before spawning the task that waits for it, so that's the current stack
+fn track_reads(tasks: &Tasks) {
+    let keys = tasks
+        .try_with_untracked(|tasks| tasks.keys().collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    READS.with_borrow_mut(|reads| {
+        reads.retain(PendingRead::is_pending);
+
+        let new_keys = keys
+            .into_iter()
+            .filter(|key| {
+                !reads
+                    .iter()
+                    .any(|read| read.key == *key && read.tasks == *tasks)
+            })
+            .collect::<Vec<_>>();
+        if new_keys.is_empty() {
+            return;
+        }
+
+        let site = read_site();
+        reads.extend(new_keys.into_iter().map(|key| PendingRead {
+            tasks: tasks.clone(),
+            key,
+            site: site.clone(),
+        }));
+    });
+}
+
+/// The innermost function of the current stack that isn't part of Leptos or of spawning
+fn read_site() -> Option<String> {
+    let stack = js_sys::Reflect::get(&js_sys::Error::new(""), &"stack".into())
+        .ok()?
+        .as_string()?;
+
+    stack.lines().filter_map(frame_name).find(|name| {
+        let path = name.trim_start_matches('<');
+        !INTERNAL_FRAMES
+            .iter()
+            .any(|internal| path.starts_with(internal))
+    })
+}
+
+/// The function of a line of a JS stack, `at name (location)` in Chromium and `name@location`
+/// elsewhere, without the hash rustc adds to symbols
+fn frame_name(line: &str) -> Option<String> {
+    let line = line.trim();
+    let name = match line.strip_prefix("at ") {
+        Some(frame) => frame.split_once(" (")?.0,
+        None => line.split_once('@')?.0,
+    };
+    let name = name.trim_start_matches('$');
+    let name = match name.rsplit_once("::h") {
+        Some((name, hash)) if hash.len() == 16 && hash.chars().all(|c| c.is_ascii_hexdigit()) => {
+            name
+        }
+        _ => name,
+    };
+
+    (!name.is_empty() && !name.contains("__wbg")).then(|| name.to_string())
+}
+
+/// Where the reads still pending in `tasks` happened
+fn read_sites(tasks: &Tasks) -> Vec<Option<String>> {
+    READS.with_borrow(|reads| {
+        reads
+            .iter()
+            .filter(|read| read.tasks == *tasks && read.is_pending())
+            .map(|read| read.site.clone())
+            .collect()
+    })
+}
+
+fn has_pending_reads(context: &SuspenseContext) -> bool {
+    context
+        .tasks
+        .try_with_untracked(|tasks| !tasks.is_empty())
+        .unwrap_or(false)
+}
+
+/// The contexts under `owner` that still have pending reads, forgetting about the resolved ones
+fn pending_contexts(owner: &Owner) -> Vec<SuspenseContext> {
+    TRACKED.with_borrow_mut(|tracked| {
+        tracked.retain(|t| has_pending_reads(&t.context));
+        tracked
+            .iter()
+            .filter(|t| t.ancestry.contains(&owner.debug_id()))
+            .map(|t| t.context.clone())
+            .collect()
+    })
+}
+
+impl<State: TestWrapperState> TestWrapper<State, Leptos> {
+    /// Waits until every resource read under a `<Suspense>` or `<Transition>` (or outside of any)
+    /// has resolved and the boundaries show their children
+    ///
+    /// Panics if that doesn't happen within a second, listing the reads that are still pending
+    pub async fn wait_for_suspense(&self) -> &Self {
+        self.wait_for_suspense_within(SUSPENSE_TIMEOUT).await
+    }
+
+    /// [`Self::wait_for_suspense`], giving up after `timeout` instead of a second
+    pub async fn wait_for_suspense_within(&self, timeout: Duration) -> &Self {
+        let owner = self.mount_owner();
+        let deadline = timers::real_now() + timeout.as_secs_f64() * 1000.0;
+
+        loop {
+            self.settle().await;
+
+            let pending = pending_contexts(owner);
+            if pending.is_empty() {
+                break;
+            }
+            if timers::real_now() > deadline {
+                panic!(
+                    "suspense didn't resolve within {timeout:?}, still pending:\n{}",
+                    describe_pending(owner, &pending)
+                );
+            }
+
+            task::next_macrotask().await;
+        }
+
+        // the boundaries only swap the fallback for their children after the last read resolved
+        self.settle().await;
+
+        self
+    }
+
+    /// Waits for some resource to start loading and then for every boundary to resolve, as with
+    /// [`Self::wait_for_suspense`]
+    ///
+    /// A `<Transition>` keeps showing its previous children while it reloads, so this is useful to
+    /// make sure an interaction actually triggered a reload. Panics if nothing starts loading
+    /// within a second
+    pub async fn wait_for_transition(&self) -> &Self {
+        self.wait_for_transition_within(SUSPENSE_TIMEOUT).await
+    }
+
+    /// [`Self::wait_for_transition`], giving up after `timeout` instead of a second, both for the
+    /// transition to start and then to resolve
+    pub async fn wait_for_transition_within(&self, timeout: Duration) -> &Self {
+        let owner = self.mount_owner();
+        let deadline = timers::real_now() + timeout.as_secs_f64() * 1000.0;
+
+        while pending_contexts(owner).is_empty() {
+            if timers::real_now() > deadline {
+                panic!("no transition started within {timeout:?}");
+            }
+
+            task::next_macrotask().await;
+        }
+
+        self.wait_for_suspense_within(timeout).await
+    }
+
+    fn mount_owner(&self) -> &Owner {
+        assert!(
+            *TRACKER_INSTALLED.get().unwrap_or(&false),
+            "pending reads can't be tracked, since a Leptos executor was set up before the first \
+             view was mounted for a test"
+        );
+
+        self.framework_ctx().owner()
+    }
+}
+
+fn describe_pending(owner: &Owner, pending: &[SuspenseContext]) -> String {
+    let root = owner.with(use_context::<SuspenseContext>);
+
+    pending
+        .iter()
+        .enumerate()
+        .map(|(i, context)| {
+            let boundary = if root
+                .as_ref()
+                .is_some_and(|root| root.tasks == context.tasks)
+            {
+                "outside of any <Suspense>/<Transition>".to_string()
+            } else {
+                format!("under <Suspense>/<Transition> #{}", i + 1)
+            };
+            let reads = read_sites(&context.tasks)
+                .into_iter()
+                .map(|site| match site {
+                    Some(site) => format!("\n      - read in `{site}`"),
+                    None => "\n      - read somewhere that couldn't be told".to_string(),
+                })
+                .collect::<String>();
+
+            format!("  - {boundary}:{reads}")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(all(test, target_family = "wasm"))]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use leptos::view;
+    use wasm_bindgen_test::*;
+
+    use crate::framework::leptos::mount_test;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    async fn waits_for_resources_under_suspense() {
+        let wrapper = mount_test(|| {
+            let greeting = LocalResource::new(|| async {
+                task::sleep(Duration::from_millis(50)).await;
+                "hello"
+            });
+
+            view! {
+                <Suspense fallback=|| view! { <p id="loading">loading</p> }>
+                    <p id="greeting">{move || greeting.get()}</p>
+                </Suspense>
+            }
+        });
+
+        wrapper.query("#loading").assert_exists();
+
+        wrapper.wait_for_suspense().await;
+
+        wrapper.query("#loading").assert_not_exists();
+        wrapper
+            .query("#greeting")
+            .assert_exists()
+            .assert_text_is("hello");
+    }
+
+    #[wasm_bindgen_test]
+    async fn waits_for_transitions_after_interactions() {
+        let wrapper = mount_test(|| {
+            let page = RwSignal::new(1);
+            let items = LocalResource::new(move || {
+                let page = page.get();
+                async move {
+                    task::sleep(Duration::from_millis(20)).await;
+                    format!("page {page}")
+                }
+            });
+
+            view! {
+                <button on:click=move |_| page.update(|page| *page += 1)>next</button>
+                <Transition fallback=|| "loading">
+                    <p id="items">{move || items.get()}</p>
+                </Transition>
+            }
+        });
+
+        wrapper.wait_for_suspense().await;
+        wrapper
+            .query("#items")
+            .assert_exists()
+            .assert_text_is("page 1");
+
+        wrapper
+            .query_as_button("button")
+            .assert_exists()
+            .click()
+            .await;
+        wrapper.wait_for_transition().await;
+
+        wrapper
+            .query("#items")
+            .assert_exists()
+            .assert_text_is("page 2");
+    }
+
+    #[wasm_bindgen_test]
+    #[should_panic = "suspense didn't resolve within 50ms, still pending:\n  - under <Suspense>/<Transition> #1:\n      - read"]
+    async fn lists_pending_reads_on_timeout() {
+        let wrapper = mount_test(|| {
+            let never = LocalResource::new(std::future::pending::<&'static str>);
+
+            view! {
+                <Suspense fallback=|| "loading">
+                    <p>{move || never.get()}</p>
+                </Suspense>
+            }
+        });
+
+        wrapper
+            .wait_for_suspense_within(Duration::from_millis(50))
+            .await;
+    }
+
+    #[wasm_bindgen_test]
+    fn parses_stack_frames() {
+        assert_eq!(
+            frame_name("    at app::Greeting::{{closure}}::h0123456789abcdef (http://x/a.wasm:wasm-function[7]:0x1)")
+                .as_deref(),
+            Some("app::Greeting::{{closure}}")
+        );
+        assert_eq!(
+            frame_name("$app::Greeting::{{closure}}::h0123456789abcdef@http://x/a.wasm:wasm-function[7]:0x1")
+                .as_deref(),
+            Some("app::Greeting::{{closure}}")
+        );
+        assert_eq!(frame_name("Error"), None);
+    }
+
+    #[wasm_bindgen_test]
+    async fn resolves_immediately_without_suspense() {
+        let wrapper = mount_test(|| view! { <p id="static">static</p> });
+
+        wrapper.wait_for_suspense().await;
+
+        wrapper
+            .query("#static")
+            .assert_exists()
+            .assert_text_is("static");
+    }
+}
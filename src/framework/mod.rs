@@ -1,7 +1,19 @@
 //! Framework-specific integrations
 
+#[cfg(feature = "dioxus")]
+pub mod dioxus;
 #[cfg(feature = "leptos")]
 pub mod leptos;
+mod settle;
+#[cfg(feature = "sycamore")]
+pub mod sycamore;
+pub mod vanilla;
+#[cfg(feature = "yew")]
+pub mod yew;
+
+use std::future::Future;
+
+pub use settle::SettleStrategy;
 
 /// A wrapper for framework-specific quirks needed to make dom-test-lib work
 pub trait Framework {
@@ -9,4 +21,9 @@ pub trait Framework {
     ///
     /// Must be cloneable since the wrappers are usually cloned a lot
     type Context: Clone;
+
+    /// Waits until the framework has applied everything caused by an interaction (renders, effects...)
+    ///
+    /// This is the default [`SettleStrategy`], called after every interaction
+    fn settle(ctx: &Self::Context) -> impl Future<Output = ()>;
 }
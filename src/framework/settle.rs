@@ -0,0 +1,35 @@
+use crate::task;
+
+use super::Framework;
+
+/// How a wrapper waits for changes to be applied after an interaction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SettleStrategy {
+    /// Whatever the framework integration does (see [`Framework::settle`])
+    #[default]
+    Framework,
+    /// A single microtask tick
+    Tick,
+    /// Until a whole macrotask passes without any changes to the DOM under the root
+    Quiescent,
+    /// The next `requestAnimationFrame`
+    AnimationFrame,
+    /// The given number of macrotasks
+    Macrotasks(usize),
+}
+
+impl SettleStrategy {
+    pub(crate) async fn settle<Fw: Framework>(self, ctx: &Fw::Context, root: &web_sys::Element) {
+        match self {
+            SettleStrategy::Framework => Fw::settle(ctx).await,
+            SettleStrategy::Tick => task::next_microtask().await,
+            SettleStrategy::Quiescent => task::until_quiescent(root).await,
+            SettleStrategy::AnimationFrame => task::next_animation_frame().await,
+            SettleStrategy::Macrotasks(n) => {
+                for _ in 0..n {
+                    task::next_macrotask().await;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use std::rc::Rc;
+
+use sycamore::reactive::RootHandle;
+
+use crate::{framework::Framework, isolation::TestRoot, task::next_macrotask};
+
+pub struct Sycamore;
+
+impl Framework for Sycamore {
+    type Context = Rc<RootGuard>;
+
+    async fn settle(_ctx: &Self::Context) {
+        flush().await;
+    }
+}
+
+/// Keeps the reactive root of a mounted view alive and disposes it (and removes the view's element)
+/// when the last wrapper holding it is dropped
+pub struct RootGuard {
+    pub(super) handle: RootHandle,
+    pub(super) _root: TestRoot,
+}
+
+impl Drop for RootGuard {
+    fn drop(&mut self) {
+        self.handle.dispose();
+    }
+}
+
+/// Waits until Sycamore has run all pending work
+///
+/// Effects run synchronously, but futures spawned by them (and their effects) run on the microtask queue,
+/// so waiting for a macrotask is enough
+pub async fn flush() {
+    next_macrotask().await;
+}
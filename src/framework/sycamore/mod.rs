@@ -0,0 +1,5 @@
+mod implementation;
+pub mod mount;
+
+pub use implementation::{RootGuard, Sycamore, flush};
+pub use mount::*;
@@ -0,0 +1,67 @@
+use std::rc::Rc;
+
+use sycamore::prelude::*;
+
+use super::implementation::{RootGuard, Sycamore};
+use crate::{isolation::TestRoot, wrapper::BaseTestWrapper};
+
+/// Mounts a view into a new element on the dom and returns a [`BaseTestWrapper`] for working with it
+///
+/// The view is rendered inside a new reactive root that's disposed (and its element removed) when the
+/// wrapper is dropped
+pub fn mount_test<F>(f: F) -> BaseTestWrapper<Sycamore>
+where
+    F: FnOnce() -> View,
+{
+    let root = TestRoot::new();
+    let test_root_node = root.element().clone();
+
+    let handle = create_root(|| sycamore::web::render_in_scope(f, &test_root_node));
+
+    BaseTestWrapper::with_root(
+        test_root_node,
+        Rc::new(RootGuard {
+            handle,
+            _root: root,
+        }),
+    )
+}
+
+#[cfg(all(test, target_family = "wasm"))]
+mod test {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn actually_mounts_it() {
+        let test_wrapper = mount_test(|| {
+            view! { span(id="mounted-span") { "hi" } }
+        });
+
+        test_wrapper.query("#mounted-span").assert_exists();
+    }
+
+    #[wasm_bindgen_test]
+    async fn flushes_after_interactions() {
+        let test_wrapper = mount_test(|| {
+            let count = create_signal(0);
+            view! {
+                span(id="count") { (count.get()) }
+                button(on:click=move |_| count.set(count.get() + 1)) { "increment" }
+            }
+        });
+
+        test_wrapper
+            .query_as_button("button")
+            .assert_exists()
+            .click()
+            .await;
+
+        test_wrapper
+            .query("#count")
+            .assert_exists()
+            .assert_text_is("1");
+    }
+}
@@ -0,0 +1,18 @@
+use std::rc::Rc;
+
+use crate::{framework::Framework, isolation::TestRoot};
+
+/// No framework at all: for testing static HTML or hand-rolled `web-sys` code
+///
+/// Wrappers are created with [`BaseTestWrapper::from_html`](crate::BaseTestWrapper::from_html),
+/// [`BaseTestWrapper::from_element`](crate::BaseTestWrapper::from_element) or
+/// [`BaseTestWrapper::from_document`](crate::BaseTestWrapper::from_document)
+pub struct Vanilla;
+
+impl Framework for Vanilla {
+    /// The root created by [`BaseTestWrapper::from_html`](crate::BaseTestWrapper::from_html), if any
+    type Context = Option<Rc<TestRoot>>;
+
+    /// There's nothing to wait for, event handlers run synchronously
+    async fn settle(_ctx: &Self::Context) {}
+}
@@ -0,0 +1,46 @@
+use std::rc::Rc;
+
+use crate::{framework::Framework, isolation::TestRoot, task::next_macrotask};
+
+pub struct Yew;
+
+impl Framework for Yew {
+    type Context = Rc<AppGuard>;
+
+    async fn settle(_ctx: &Self::Context) {
+        flush().await;
+    }
+}
+
+/// Keeps a Yew app alive and destroys it (and removes its root) when the last wrapper holding it is
+/// dropped
+///
+/// The [`yew::AppHandle`] is erased to avoid generics in the [`Framework`] struct
+pub struct AppGuard {
+    destroy: Option<Box<dyn FnOnce()>>,
+    _root: TestRoot,
+}
+
+impl AppGuard {
+    pub(super) fn new<C: yew::BaseComponent>(handle: yew::AppHandle<C>, root: TestRoot) -> Self {
+        Self {
+            destroy: Some(Box::new(move || handle.destroy())),
+            _root: root,
+        }
+    }
+}
+
+impl Drop for AppGuard {
+    fn drop(&mut self) {
+        if let Some(destroy) = self.destroy.take() {
+            destroy();
+        }
+    }
+}
+
+/// Waits until Yew's scheduler has run all pending renders
+///
+/// The scheduler runs on the microtask queue, so waiting for a macrotask is enough
+pub async fn flush() {
+    next_macrotask().await;
+}
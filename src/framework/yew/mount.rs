@@ -0,0 +1,94 @@
+use std::rc::Rc;
+
+use super::implementation::{AppGuard, Yew, flush};
+use crate::{isolation::TestRoot, wrapper::BaseTestWrapper};
+use yew::prelude::*;
+
+/// Mounts some html into a new element on the dom and returns a [`BaseTestWrapper`] for working with it
+///
+/// It's async because Yew renders on its scheduler, so the DOM is empty until it runs
+pub async fn mount_test<F>(f: F) -> BaseTestWrapper<Yew>
+where
+    F: Fn() -> Html + 'static,
+{
+    mount_component_test::<RenderFn>(RenderFnProps { render: Rc::new(f) }).await
+}
+
+/// Mounts a component with the given props into a new element on the dom and returns a [`BaseTestWrapper`]
+/// for working with it
+pub async fn mount_component_test<C: BaseComponent>(props: C::Properties) -> BaseTestWrapper<Yew> {
+    let test_root = TestRoot::new();
+    let test_root_node = test_root.element().clone();
+
+    let handle = yew::Renderer::<C>::with_root_and_props(test_root_node.clone(), props).render();
+    flush().await;
+
+    BaseTestWrapper::with_root(test_root_node, Rc::new(AppGuard::new(handle, test_root)))
+}
+
+#[derive(Properties)]
+struct RenderFnProps {
+    render: Rc<dyn Fn() -> Html>,
+}
+
+impl PartialEq for RenderFnProps {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.render, &other.render)
+    }
+}
+
+/// Renders whatever html the closure in its props returns
+#[function_component]
+fn RenderFn(props: &RenderFnProps) -> Html {
+    (props.render)()
+}
+
+#[cfg(all(test, target_family = "wasm"))]
+mod test {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[function_component]
+    fn Counter() -> Html {
+        let count = use_state(|| 0);
+        let onclick = {
+            let count = count.clone();
+            Callback::from(move |_| count.set(*count + 1))
+        };
+
+        html! {
+            <>
+                <span id="count">{ *count }</span>
+                <button {onclick}>{ "increment" }</button>
+            </>
+        }
+    }
+
+    #[wasm_bindgen_test]
+    async fn actually_mounts_it() {
+        let test_wrapper = mount_test(|| {
+            html! { <span id="mounted-span">{ "hi" }</span> }
+        })
+        .await;
+
+        test_wrapper.query("#mounted-span").assert_exists();
+    }
+
+    #[wasm_bindgen_test]
+    async fn flushes_after_interactions() {
+        let test_wrapper = mount_component_test::<Counter>(()).await;
+
+        test_wrapper
+            .query_as_button("button")
+            .assert_exists()
+            .click()
+            .await;
+
+        test_wrapper
+            .query("#count")
+            .assert_exists()
+            .assert_text_is("1");
+    }
+}
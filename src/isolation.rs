@@ -0,0 +1,308 @@
+//! Keeping tests from seeing what other tests left behind
+//!
+//! wasm-bindgen-test runs every test of a crate in the same page, so anything a test adds to the
+//! document is still there for the next ones. The elements views are mounted into are removed once
+//! the last wrapper using them is dropped, and [`assert_no_leftovers`] catches anything else left
+//! in `<body>` or `<head>`. The rest of the page's global state can be reset with [`reset_globals`].
+
+use std::cell::{Cell, OnceCell};
+
+use wasm_bindgen::JsCast as _;
+
+use crate::{storage, util::NodeListExt as _};
+
+/// The attribute that marks the elements created by [`TestRoot::new`]
+const TEST_ROOT_ATTR: &str = "data-test-root";
+
+/// How much of a leftover node's HTML is shown when reporting it
+const MAX_LEFTOVER_HTML_LEN: usize = 80;
+
+thread_local! {
+    static BASELINE: OnceCell<Baseline> = const { OnceCell::new() };
+    static INTERNAL: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f` as the library's own doing, so what it registers (e.g. event listeners on `window`)
+/// isn't blamed on the views under test
+pub(crate) fn internally<T>(f: impl FnOnce() -> T) -> T {
+    let was_internal = INTERNAL.replace(true);
+    let result = f();
+    INTERNAL.set(was_internal);
+    result
+}
+
+/// Whether this runs inside of [`internally`]
+#[cfg(feature = "leptos")]
+pub(crate) fn is_internal() -> bool {
+    INTERNAL.get()
+}
+
+/// The page as it was before the first test touched it
+struct Baseline {
+    body: Vec<web_sys::Node>,
+    head: Vec<web_sys::Node>,
+    title: String,
+}
+
+fn document() -> web_sys::Document {
+    web_sys::window().unwrap().document().unwrap()
+}
+
+fn with_baseline<T>(f: impl FnOnce(&Baseline) -> T) -> T {
+    BASELINE.with(|baseline| {
+        f(baseline.get_or_init(|| {
+            let document = document();
+            Baseline {
+                body: document
+                    .body()
+                    .unwrap()
+                    .child_nodes()
+                    .into_iterator()
+                    .collect(),
+                head: document
+                    .head()
+                    .unwrap()
+                    .child_nodes()
+                    .into_iterator()
+                    .collect(),
+                title: document.title(),
+            }
+        }))
+    })
+}
+
+/// A new element on the dom for mounting a test into, removed from the dom when dropped
+///
+/// Framework contexts keep it alongside whatever keeps their app alive, so it goes away with the
+/// last wrapper using it
+pub struct TestRoot(web_sys::Element);
+
+impl TestRoot {
+    pub(crate) fn new() -> Self {
+        with_baseline(|_| ());
+
+        let document = document();
+        let elem = document.create_element("section").unwrap();
+        elem.set_attribute(TEST_ROOT_ATTR, "").unwrap();
+        let _ = document.body().unwrap().append_child(&elem);
+
+        Self(elem)
+    }
+
+    pub fn element(&self) -> &web_sys::Element {
+        &self.0
+    }
+}
+
+impl Drop for TestRoot {
+    fn drop(&mut self) {
+        self.0.remove();
+    }
+}
+
+/// The nodes in `<body>` and `<head>` that weren't there before the first test and don't belong to
+/// a wrapper that's still alive
+pub fn leftover_nodes() -> Vec<web_sys::Node> {
+    with_baseline(|baseline| {
+        let document = document();
+        let body = document.body().unwrap().child_nodes().into_iterator();
+        let head = document.head().unwrap().child_nodes().into_iterator();
+
+        body.filter(|node| !baseline.body.contains(node) && !is_test_root(node))
+            .chain(head.filter(|node| !baseline.head.contains(node)))
+            // whitespace between elements isn't worth reporting
+            .filter(|node| {
+                node.node_type() != web_sys::Node::TEXT_NODE
+                    || !node.text_content().unwrap_or_default().trim().is_empty()
+            })
+            .collect()
+    })
+}
+
+/// Whether `elem` is inside the root of a wrapper other than the one with `own_root`
+pub(crate) fn in_other_test_root(elem: &web_sys::Element, own_root: &web_sys::Element) -> bool {
+    elem.closest(&format!("[{TEST_ROOT_ATTR}]"))
+        .unwrap()
+        .is_some_and(|root| &root != own_root)
+}
+
+fn is_test_root(node: &web_sys::Node) -> bool {
+    node.dyn_ref::<web_sys::Element>()
+        .is_some_and(|elem| elem.has_attribute(TEST_ROOT_ATTR))
+}
+
+/// Asserts that there's nothing left in `<body>` or `<head>` besides what was there before the first
+/// test and the roots of the wrappers that are still alive, e.g. a portal or a toast that was never
+/// removed
+pub fn assert_no_leftovers() {
+    let leftovers = leftover_nodes();
+
+    assert!(
+        leftovers.is_empty(),
+        "found nodes left behind in the document:\n  - {}",
+        leftovers
+            .iter()
+            .map(describe_node)
+            .collect::<Vec<_>>()
+            .join("\n  - ")
+    );
+}
+
+fn describe_node(node: &web_sys::Node) -> String {
+    let html = match node.dyn_ref::<web_sys::Element>() {
+        Some(elem) => elem.outer_html(),
+        None => node.text_content().unwrap_or_default(),
+    };
+
+    if html.chars().count() > MAX_LEFTOVER_HTML_LEN {
+        let truncated = html.chars().take(MAX_LEFTOVER_HTML_LEN).collect::<String>();
+        format!("{truncated}...")
+    } else {
+        html
+    }
+}
+
+/// Which parts of the page's global state [`reset_globals`] resets
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResetOptions {
+    focus: bool,
+    scroll: bool,
+    hash: bool,
+    storage: bool,
+    title: bool,
+}
+
+impl ResetOptions {
+    /// Resets everything
+    pub fn all() -> Self {
+        Self {
+            focus: true,
+            scroll: true,
+            hash: true,
+            storage: true,
+            title: true,
+        }
+    }
+
+    /// Blurs the focused element
+    pub fn focus(mut self) -> Self {
+        self.focus = true;
+        self
+    }
+
+    /// Scrolls the window back to the top left corner
+    pub fn scroll(mut self) -> Self {
+        self.scroll = true;
+        self
+    }
+
+    /// Removes the hash from the URL, without adding a history entry
+    pub fn hash(mut self) -> Self {
+        self.hash = true;
+        self
+    }
+
+    /// Clears `localStorage` and `sessionStorage`
+    pub fn storage(mut self) -> Self {
+        self.storage = true;
+        self
+    }
+
+    /// Restores the document's title to what it was before the first test
+    pub fn title(mut self) -> Self {
+        self.title = true;
+        self
+    }
+}
+
+/// Resets the parts of the page's global state chosen in `options`, usually at the start of a test
+///
+/// ```ignore
+/// isolation::reset_globals(ResetOptions::default().hash().storage());
+/// ```
+pub fn reset_globals(options: ResetOptions) {
+    let window = web_sys::window().unwrap();
+    let document = document();
+
+    if options.focus {
+        if let Some(focused) = document.active_element() {
+            if let Ok(focused) = focused.dyn_into::<web_sys::HtmlElement>() {
+                focused.blur().unwrap();
+            }
+        }
+    }
+    if options.scroll {
+        window.scroll_to_with_x_and_y(0.0, 0.0);
+    }
+    if options.hash && !window.location().hash().unwrap().is_empty() {
+        let location = window.location();
+        let url = format!(
+            "{}{}",
+            location.pathname().unwrap(),
+            location.search().unwrap()
+        );
+        window
+            .history()
+            .unwrap()
+            .replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&url))
+            .unwrap();
+    }
+    if options.storage {
+        storage::clear_storage();
+    }
+    if options.title {
+        with_baseline(|baseline| document.set_title(&baseline.title));
+    }
+}
+
+#[cfg(all(test, target_family = "wasm"))]
+mod tests {
+    use super::*;
+    use crate::{BaseTestWrapper, Vanilla};
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn removes_roots_when_dropped() {
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html(r#"<p id="dropped-root">hi</p>"#);
+        let derived = wrapper.query("p");
+        drop(wrapper);
+        assert!(document().get_element_by_id("dropped-root").is_some());
+
+        drop(derived);
+        assert!(document().get_element_by_id("dropped-root").is_none());
+    }
+
+    #[wasm_bindgen_test]
+    fn finds_leftovers() {
+        let _wrapper = BaseTestWrapper::<Vanilla>::from_html("<p>still in use</p>");
+        let toast = document().create_element("div").unwrap();
+        toast.set_inner_html("saved!");
+        document().body().unwrap().append_child(&toast).unwrap();
+
+        // other tests share the document, so only the nodes made here are looked at
+        assert!(leftover_nodes().contains(toast.as_ref()));
+
+        toast.remove();
+        assert!(!leftover_nodes().contains(toast.as_ref()));
+    }
+
+    #[wasm_bindgen_test]
+    fn resets_globals() {
+        document().set_title("changed by a test");
+        web_sys::window()
+            .unwrap()
+            .location()
+            .set_hash("section")
+            .unwrap();
+
+        reset_globals(ResetOptions::default().title().hash());
+
+        assert_eq!(
+            document().title(),
+            with_baseline(|baseline| baseline.title.clone())
+        );
+        assert_eq!(web_sys::window().unwrap().location().hash().unwrap(), "");
+    }
+}
@@ -1,12 +1,30 @@
+pub mod aria;
+pub mod console;
 mod event;
+pub mod event_spy;
 pub mod framework;
+pub mod isolation;
+pub mod mutations;
+pub mod network;
+pub mod shims;
+pub mod snapshot;
+pub mod storage;
+mod task;
+pub mod timers;
 pub mod util;
 mod wrapper;
 
 // TODO: remove dis?
 use event::*;
 
+pub use framework::vanilla::Vanilla;
 pub use wrapper::BaseTestWrapper;
 
+#[cfg(feature = "dioxus")]
+pub use framework::dioxus;
 #[cfg(feature = "leptos")]
 pub use framework::leptos;
+#[cfg(feature = "sycamore")]
+pub use framework::sycamore;
+#[cfg(feature = "yew")]
+pub use framework::yew;
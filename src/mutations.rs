@@ -0,0 +1,254 @@
+//! Recording of the DOM mutations caused by an interaction
+
+use std::{cell::RefCell, future::Future, rc::Rc};
+
+use wasm_bindgen::{JsCast as _, closure::Closure};
+
+use crate::util::NodeListExt as _;
+
+/// A single change to the DOM
+#[derive(Debug, Clone)]
+pub enum Mutation {
+    /// `node` was added as a child of `parent`
+    Added {
+        parent: web_sys::Node,
+        node: web_sys::Node,
+    },
+    /// `node` was removed from `parent`
+    Removed {
+        parent: web_sys::Node,
+        node: web_sys::Node,
+    },
+    /// An attribute of `target` changed. `new_value` is its value at the end of the recording
+    Attribute {
+        target: web_sys::Element,
+        name: String,
+        old_value: Option<String>,
+        new_value: Option<String>,
+    },
+    /// The content of a text node changed. `new_value` is its content at the end of the recording
+    Text {
+        target: web_sys::Node,
+        old_value: Option<String>,
+        new_value: Option<String>,
+    },
+}
+
+impl Mutation {
+    /// The node where the change happened: the parent for added and removed nodes, the node
+    /// itself otherwise
+    pub fn target(&self) -> &web_sys::Node {
+        match self {
+            Mutation::Added { parent, .. } | Mutation::Removed { parent, .. } => parent,
+            Mutation::Attribute { target, .. } => target,
+            Mutation::Text { target, .. } => target,
+        }
+    }
+
+    fn from_record(record: web_sys::MutationRecord) -> Vec<Mutation> {
+        let target = record.target().expect("mutation record has no target");
+
+        match record.type_().as_str() {
+            "childList" => record
+                .added_nodes()
+                .into_iterator()
+                .map(|node| Mutation::Added {
+                    parent: target.clone(),
+                    node,
+                })
+                .chain(
+                    record
+                        .removed_nodes()
+                        .into_iterator()
+                        .map(|node| Mutation::Removed {
+                            parent: target.clone(),
+                            node,
+                        }),
+                )
+                .collect(),
+            "attributes" => {
+                let target = target.unchecked_into::<web_sys::Element>();
+                let name = record.attribute_name().unwrap_or_default();
+                vec![Mutation::Attribute {
+                    new_value: target.get_attribute(&name),
+                    old_value: record.old_value(),
+                    target,
+                    name,
+                }]
+            }
+            "characterData" => vec![Mutation::Text {
+                new_value: target.text_content(),
+                old_value: record.old_value(),
+                target,
+            }],
+            other => panic!("unknown mutation type `{other}`"),
+        }
+    }
+}
+
+impl std::fmt::Display for Mutation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Mutation::Added { parent, node } => {
+                write!(f, "added {} to {}", describe(node), describe(parent))
+            }
+            Mutation::Removed { parent, node } => {
+                write!(f, "removed {} from {}", describe(node), describe(parent))
+            }
+            Mutation::Attribute {
+                target,
+                name,
+                old_value,
+                new_value,
+            } => write!(
+                f,
+                "changed attribute `{name}` of {} from {old_value:?} to {new_value:?}",
+                describe(target)
+            ),
+            Mutation::Text {
+                target,
+                old_value,
+                new_value,
+            } => write!(
+                f,
+                "changed text of {} from {old_value:?} to {new_value:?}",
+                describe(target)
+            ),
+        }
+    }
+}
+
+/// A short description of a node for error messages, like `<span id="x">` or `text "hi"`
+fn describe(node: &web_sys::Node) -> String {
+    match node.dyn_ref::<web_sys::Element>() {
+        Some(elem) if !elem.id().is_empty() => {
+            format!("<{} id=\"{}\">", elem.local_name(), elem.id())
+        }
+        Some(elem) => format!("<{}>", elem.local_name()),
+        None => format!("text {:?}", node.text_content().unwrap_or_default()),
+    }
+}
+
+/// All mutations recorded during an interaction, in order
+#[derive(Debug, Clone, Default)]
+pub struct Mutations(pub Vec<Mutation>);
+
+impl Mutations {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Mutation> {
+        self.0.iter()
+    }
+
+    /// Nodes added anywhere in the tree
+    pub fn added(&self) -> impl Iterator<Item = &web_sys::Node> {
+        self.0.iter().filter_map(|mutation| match mutation {
+            Mutation::Added { node, .. } => Some(node),
+            _ => None,
+        })
+    }
+
+    /// Nodes removed anywhere in the tree
+    pub fn removed(&self) -> impl Iterator<Item = &web_sys::Node> {
+        self.0.iter().filter_map(|mutation| match mutation {
+            Mutation::Removed { node, .. } => Some(node),
+            _ => None,
+        })
+    }
+
+    /// Asserts that nothing changed in the DOM
+    pub fn assert_no_mutations(&self) -> &Self {
+        assert!(
+            self.is_empty(),
+            "expected no mutations, but got:\n{}",
+            self.describe_all(self.iter())
+        );
+        self
+    }
+
+    /// Asserts that every mutation happened inside the element(s) matching the selector (inclusive)
+    pub fn assert_only_changed(&self, selector: &str) -> &Self {
+        let outside = self
+            .iter()
+            .filter(|mutation| {
+                let target = mutation.target();
+                let elem = match target.dyn_ref::<web_sys::Element>() {
+                    Some(elem) => Some(elem.clone()),
+                    None => target.parent_element(),
+                };
+                elem.is_none_or(|elem| elem.closest(selector).unwrap().is_none())
+            })
+            .collect::<Vec<_>>();
+
+        assert!(
+            outside.is_empty(),
+            "expected only `{selector}` to change, but there were mutations outside of it:\n{}",
+            self.describe_all(outside.into_iter())
+        );
+        self
+    }
+
+    fn describe_all<'a>(&self, mutations: impl Iterator<Item = &'a Mutation>) -> String {
+        mutations
+            .map(|mutation| format!("  {mutation}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Records all mutations under `root` while the future returned by `f` runs
+pub async fn record<F, Fut>(root: &web_sys::Node, f: F) -> (Mutations, Fut::Output)
+where
+    F: FnOnce() -> Fut,
+    Fut: Future,
+{
+    let records = Rc::new(RefCell::new(Vec::new()));
+
+    let records_clone = Rc::clone(&records);
+    let callback = Closure::<dyn FnMut(js_sys::Array)>::new(move |batch: js_sys::Array| {
+        records_clone.borrow_mut().extend(
+            batch
+                .iter()
+                .map(|record| record.unchecked_into::<web_sys::MutationRecord>()),
+        );
+    });
+
+    let observer = web_sys::MutationObserver::new(callback.as_ref().unchecked_ref())
+        .expect("couldn't create mutation observer");
+    let options = web_sys::MutationObserverInit::new();
+    options.set_subtree(true);
+    options.set_child_list(true);
+    options.set_attributes(true);
+    options.set_attribute_old_value(true);
+    options.set_character_data(true);
+    options.set_character_data_old_value(true);
+    observer
+        .observe_with_options(root, &options)
+        .expect("couldn't observe root");
+
+    let output = f().await;
+
+    // records that haven't been delivered to the callback yet
+    let pending = observer.take_records();
+    observer.disconnect();
+
+    let mut records = records.take();
+    records.extend(
+        pending
+            .iter()
+            .map(|record| record.unchecked_into::<web_sys::MutationRecord>()),
+    );
+
+    let mutations = records
+        .into_iter()
+        .flat_map(Mutation::from_record)
+        .collect();
+
+    (Mutations(mutations), output)
+}
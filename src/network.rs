@@ -0,0 +1,916 @@
+//! In-page mocks for the network
+//!
+//! While a [`MockNetwork`] is alive, `fetch` and `XMLHttpRequest` are replaced so requests are
+//! answered by handlers defined in the test instead of going out, and every request is recorded for
+//! assertions:
+//!
+//! ```ignore
+//! let network = MockNetwork::start();
+//! network
+//!     .on(Route::get("/api/items"), |_| MockResponse::json(&["Buy milk"]))
+//!     .on(Route::post("/api/items"), |_| MockResponse::status(201));
+//!
+//! let wrapper = mount_test(|| view! { <Items /> });
+//! // ...
+//! network.assert_requested("POST", "/api/items", Some(r#"{"title":"Buy eggs"}"#));
+//! ```
+//!
+//! `XMLHttpRequest` is emulated on top of `fetch`, so both go through the same handlers. Synchronous
+//! requests aren't supported.
+//!
+//! Everything in the library that intercepts `fetch` (like the Leptos server function mocks) does it
+//! as a layer of a single patch, so they can be created and dropped in any order. The most recent
+//! layer sees a request first and passes it on to the ones below if it doesn't answer it.
+
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use serde::{Serialize, de::DeserializeOwned};
+use wasm_bindgen::{JsCast as _, JsValue, closure::Closure};
+use wasm_bindgen_futures::JsFuture;
+
+use crate::task;
+
+type Handler = Rc<dyn Fn(&MockRequest) -> MockResponse>;
+
+type FetchFn = dyn Fn(JsValue, JsValue) -> js_sys::Promise;
+
+/// Intercepts a call to `fetch`: answers it, or passes it on with [`Next::fetch`]
+pub(crate) type FetchLayer = Rc<dyn Fn(JsValue, JsValue, Next) -> js_sys::Promise>;
+
+thread_local! {
+    static FETCH: RefCell<FetchPatch> = RefCell::default();
+    static XHR: RefCell<XhrPatch> = RefCell::default();
+}
+
+/// The single patch of `fetch`, which goes through the layers from the most recent one
+#[derive(Default)]
+struct FetchPatch {
+    next_id: usize,
+    layers: Vec<(usize, FetchLayer)>,
+    /// The `fetch` from before the first layer was pushed
+    original: Option<js_sys::Function>,
+    /// Never dropped, since code may have kept a reference to it
+    dispatcher: Option<Closure<FetchFn>>,
+}
+
+impl FetchPatch {
+    fn dispatcher(&mut self) -> JsValue {
+        self.dispatcher
+            .get_or_insert_with(|| Closure::new(dispatch_fetch))
+            .as_ref()
+            .clone()
+    }
+}
+
+/// Decides what happens to a request that no layer answered, see [`Next::or_else`]
+type Unhandled = Box<dyn FnOnce(JsValue, JsValue, Next) -> js_sys::Promise>;
+
+/// The layers below the one handling a request, down to the original `fetch`
+pub(crate) struct Next {
+    layers: Vec<FetchLayer>,
+    unhandled: Vec<Unhandled>,
+    original: js_sys::Function,
+}
+
+impl Next {
+    /// Passes the request on to the layer below
+    pub(crate) fn fetch(mut self, input: JsValue, init: JsValue) -> js_sys::Promise {
+        if let Some(layer) = self.layers.pop() {
+            return layer(input, init, self);
+        }
+        if let Some(unhandled) = self.unhandled.pop() {
+            return unhandled(input, init, self);
+        }
+
+        match self.original.call2(&JsValue::NULL, &input, &init) {
+            Ok(response) => response.unchecked_into(),
+            Err(err) => js_sys::Promise::reject(&err),
+        }
+    }
+
+    /// Has `unhandled` called instead of the original `fetch` if none of the layers below answers
+    /// the request. It can pass the request on to the original through the [`Next`] it's given
+    pub(crate) fn or_else(
+        mut self,
+        unhandled: impl FnOnce(JsValue, JsValue, Next) -> js_sys::Promise + 'static,
+    ) -> Self {
+        self.unhandled.push(Box::new(unhandled));
+        self
+    }
+}
+
+/// Removes a layer from `fetch` when dropped, see [`push_fetch_layer`]
+pub(crate) struct FetchLayerGuard(usize);
+
+/// Adds a layer on top of `fetch`, which stays until the returned guard is dropped
+pub(crate) fn push_fetch_layer(layer: FetchLayer) -> FetchLayerGuard {
+    FETCH.with_borrow_mut(|fetch| {
+        if fetch.layers.is_empty() {
+            let global = js_sys::global();
+            fetch.original = Some(
+                js_sys::Reflect::get(&global, &"fetch".into())
+                    .unwrap()
+                    .unchecked_into(),
+            );
+            js_sys::Reflect::set(&global, &"fetch".into(), &fetch.dispatcher()).unwrap();
+        }
+
+        let id = fetch.next_id;
+        fetch.next_id += 1;
+        fetch.layers.push((id, layer));
+        FetchLayerGuard(id)
+    })
+}
+
+impl Drop for FetchLayerGuard {
+    fn drop(&mut self) {
+        FETCH.with_borrow_mut(|fetch| {
+            fetch.layers.retain(|(id, _)| *id != self.0);
+            if !fetch.layers.is_empty() {
+                return;
+            }
+
+            // if something else replaced `fetch` since, it's left alone and the dispatcher just
+            // forwards to the original
+            let global = js_sys::global();
+            let current = js_sys::Reflect::get(&global, &"fetch".into()).unwrap();
+            if current == fetch.dispatcher() {
+                if let Some(original) = &fetch.original {
+                    js_sys::Reflect::set(&global, &"fetch".into(), original).unwrap();
+                }
+            }
+        });
+    }
+}
+
+fn dispatch_fetch(input: JsValue, init: JsValue) -> js_sys::Promise {
+    let next = FETCH.with_borrow(|fetch| Next {
+        layers: fetch
+            .layers
+            .iter()
+            .map(|(_, layer)| Rc::clone(layer))
+            .collect(),
+        unhandled: Vec::new(),
+        original: fetch.original.clone().unwrap(),
+    });
+
+    next.fetch(input, init)
+}
+
+/// The fake `XMLHttpRequest`, installed for as long as some [`MockNetwork`] is alive
+#[derive(Default)]
+struct XhrPatch {
+    users: usize,
+    original: Option<JsValue>,
+}
+
+/// Keeps the fake `XMLHttpRequest` installed while alive
+struct XhrGuard;
+
+impl XhrGuard {
+    fn new() -> Self {
+        XHR.with_borrow_mut(|xhr| {
+            if xhr.users == 0 {
+                let global = js_sys::global();
+                xhr.original =
+                    Some(js_sys::Reflect::get(&global, &"XMLHttpRequest".into()).unwrap());
+                let fake_xhr = js_sys::Function::new_no_args(FAKE_XHR)
+                    .call0(&JsValue::NULL)
+                    .unwrap();
+                js_sys::Reflect::set(&global, &"XMLHttpRequest".into(), &fake_xhr).unwrap();
+            }
+            xhr.users += 1;
+        });
+
+        Self
+    }
+}
+
+impl Drop for XhrGuard {
+    fn drop(&mut self) {
+        XHR.with_borrow_mut(|xhr| {
+            xhr.users -= 1;
+            if xhr.users == 0 {
+                if let Some(original) = xhr.original.take() {
+                    js_sys::Reflect::set(&js_sys::global(), &"XMLHttpRequest".into(), &original)
+                        .unwrap();
+                }
+            }
+        });
+    }
+}
+
+/// An `XMLHttpRequest` replacement that sends its requests through `fetch`, and so through the
+/// mocks. It's built by evaluating this, since it has to be a class
+const FAKE_XHR: &str = r#"
+return class XMLHttpRequest extends EventTarget {
+    static UNSENT = 0;
+    static OPENED = 1;
+    static HEADERS_RECEIVED = 2;
+    static LOADING = 3;
+    static DONE = 4;
+
+    constructor() {
+        super();
+        this.readyState = 0;
+        this.status = 0;
+        this.statusText = "";
+        this.responseType = "";
+        this.response = null;
+        this.responseText = "";
+        this.responseURL = "";
+        this.timeout = 0;
+        this.withCredentials = false;
+        this.upload = new EventTarget();
+        this._headers = new Headers();
+        this._responseHeaders = null;
+        this._aborted = false;
+    }
+
+    open(method, url, isAsync = true) {
+        if (isAsync === false) {
+            throw new Error("synchronous XMLHttpRequest isn't supported by the network mocks");
+        }
+        this._method = method;
+        this._url = new URL(url, location.href).href;
+        this._setReadyState(1);
+    }
+
+    setRequestHeader(name, value) {
+        this._headers.append(name, value);
+    }
+
+    getResponseHeader(name) {
+        return this._responseHeaders ? this._responseHeaders.get(name) : null;
+    }
+
+    getAllResponseHeaders() {
+        let all = "";
+        if (this._responseHeaders) {
+            this._responseHeaders.forEach((value, name) => {
+                all += name + ": " + value + "\r\n";
+            });
+        }
+        return all;
+    }
+
+    overrideMimeType() {}
+
+    abort() {
+        if (this.readyState === 0 || this.readyState === 4) {
+            return;
+        }
+        this._aborted = true;
+        this._setReadyState(4);
+        this._emit("abort");
+        this._emit("loadend");
+    }
+
+    send(body = null) {
+        this._emit("loadstart");
+        fetch(this._url, { method: this._method, headers: this._headers, body }).then(
+            async (response) => {
+                if (this._aborted) return;
+                this.status = response.status;
+                this.statusText = response.statusText;
+                this.responseURL = response.url || this._url;
+                this._responseHeaders = response.headers;
+                this._setReadyState(2);
+
+                const text = await response.text();
+                if (this._aborted) return;
+                this._setReadyState(3);
+                this.responseText = text;
+                if (this.responseType === "json") {
+                    try {
+                        this.response = JSON.parse(text);
+                    } catch {
+                        this.response = null;
+                    }
+                } else {
+                    this.response = text;
+                }
+                this._setReadyState(4);
+                this._emit("load");
+                this._emit("loadend");
+            },
+            () => {
+                if (this._aborted) return;
+                this._setReadyState(4);
+                this._emit("error");
+                this._emit("loadend");
+            },
+        );
+    }
+
+    _setReadyState(state) {
+        this.readyState = state;
+        this._emit("readystatechange");
+    }
+
+    _emit(type) {
+        const event = new ProgressEvent(type);
+        this.dispatchEvent(event);
+        const handler = this["on" + type];
+        if (typeof handler === "function") {
+            handler.call(this, event);
+        }
+    }
+};
+"#;
+
+/// Which requests a handler answers: a method, a URL pattern and optionally a body
+///
+/// The pattern is matched against the path of the URL, or against the whole URL if it starts with
+/// `http`. A `*` matches anything and a `:name` segment matches a single segment, e.g.
+/// `/api/items/:id` or `https://cdn.example.com/*`. The query string is only matched if the
+/// pattern has one
+#[derive(Debug, Clone)]
+pub struct Route {
+    method: String,
+    pattern: String,
+    body: Option<String>,
+}
+
+impl Route {
+    pub fn new(method: &str, pattern: &str) -> Self {
+        Self {
+            method: method.to_uppercase(),
+            pattern: pattern.to_string(),
+            body: None,
+        }
+    }
+
+    pub fn get(pattern: &str) -> Self {
+        Self::new("GET", pattern)
+    }
+
+    pub fn post(pattern: &str) -> Self {
+        Self::new("POST", pattern)
+    }
+
+    pub fn put(pattern: &str) -> Self {
+        Self::new("PUT", pattern)
+    }
+
+    pub fn patch(pattern: &str) -> Self {
+        Self::new("PATCH", pattern)
+    }
+
+    pub fn delete(pattern: &str) -> Self {
+        Self::new("DELETE", pattern)
+    }
+
+    /// Only matches requests with this body. If both are JSON they're compared as values, so
+    /// formatting and the order of keys don't matter
+    pub fn with_body(mut self, body: &str) -> Self {
+        self.body = Some(body.to_string());
+        self
+    }
+
+    /// Only matches requests with this body serialized as JSON, see [`Self::with_body`]
+    pub fn with_json_body(self, body: &impl Serialize) -> Self {
+        self.with_body(&serde_json::to_string(body).unwrap())
+    }
+
+    fn matches(&self, request: &MockRequest) -> bool {
+        self.method == request.method
+            && url_matches(&self.pattern, request)
+            && self
+                .body
+                .as_ref()
+                .is_none_or(|body| bodies_match(body, &request.body))
+    }
+}
+
+/// A request made while a [`MockNetwork`] was alive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockRequest {
+    method: String,
+    url: String,
+    path: String,
+    query: String,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl MockRequest {
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    /// The whole URL, resolved against the page's
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The query string, with the leading `?` if it isn't empty
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// Parses the body as JSON, panics if it can't
+    pub fn json<T: DeserializeOwned>(&self) -> T {
+        serde_json::from_str(&self.body).unwrap_or_else(|err| {
+            panic!(
+                "the body of {} {} isn't valid JSON for `{}`: {err}",
+                self.method,
+                self.url,
+                std::any::type_name::<T>()
+            )
+        })
+    }
+
+    async fn read(input: &JsValue, init: &JsValue) -> Result<Self, JsValue> {
+        // reading the body consumes it, so a copy is read and the original stays usable in case the
+        // request has to go out after all
+        let request = match input.dyn_ref::<web_sys::Request>() {
+            Some(request) => web_sys::Request::new_with_request_and_init(
+                &request.clone()?,
+                init.unchecked_ref(),
+            )?,
+            None => {
+                let url = match input.dyn_ref::<web_sys::Url>() {
+                    Some(url) => url.href(),
+                    None => input.as_string().unwrap_or_default(),
+                };
+                web_sys::Request::new_with_str_and_init(&url, init.unchecked_ref())?
+            }
+        };
+
+        let url = web_sys::Url::new(&request.url())?;
+        let mut headers = Vec::new();
+        for entry in request.headers().entries() {
+            let entry: js_sys::Array = entry?.unchecked_into();
+            headers.push((
+                entry.get(0).as_string().unwrap_or_default(),
+                entry.get(1).as_string().unwrap_or_default(),
+            ));
+        }
+        let body = JsFuture::from(request.text()?).await?;
+
+        Ok(Self {
+            method: request.method().to_uppercase(),
+            url: url.href(),
+            path: url.pathname(),
+            query: url.search(),
+            headers,
+            body: body.as_string().unwrap_or_default(),
+        })
+    }
+}
+
+impl std::fmt::Display for MockRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.method, self.url)?;
+        if !self.body.is_empty() {
+            write!(f, " {}", self.body)?;
+        }
+        Ok(())
+    }
+}
+
+/// What a handler answers a request with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+    delay: Duration,
+    network_error: bool,
+}
+
+impl MockResponse {
+    /// An empty response with the given status
+    pub fn status(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: None,
+            delay: Duration::ZERO,
+            network_error: false,
+        }
+    }
+
+    /// A `200` with `body` serialized as JSON
+    pub fn json(body: &impl Serialize) -> Self {
+        Self::status(200)
+            .with_header("content-type", "application/json")
+            .with_body(&serde_json::to_string(body).unwrap())
+    }
+
+    /// A `200` with `body` as plain text
+    pub fn text(body: &str) -> Self {
+        Self::status(200)
+            .with_header("content-type", "text/plain")
+            .with_body(body)
+    }
+
+    /// Fails the request as if the network was down, so `fetch` rejects
+    pub fn network_error() -> Self {
+        Self {
+            network_error: true,
+            ..Self::status(0)
+        }
+    }
+
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn with_body(mut self, body: &str) -> Self {
+        self.body = Some(body.to_string());
+        self
+    }
+
+    /// Waits this long before answering, e.g. for checking loading states
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    async fn into_js(self) -> Result<JsValue, JsValue> {
+        if !self.delay.is_zero() {
+            task::sleep(self.delay).await;
+        }
+        if self.network_error {
+            return Err(js_sys::TypeError::new("Failed to fetch (mocked network error)").into());
+        }
+
+        let init = web_sys::ResponseInit::new();
+        init.set_status(self.status);
+        let headers = web_sys::Headers::new()?;
+        for (name, value) in &self.headers {
+            headers.append(name, value)?;
+        }
+        init.set_headers(&headers);
+
+        web_sys::Response::new_with_opt_str_and_init(self.body.as_deref(), &init).map(Into::into)
+    }
+}
+
+#[derive(Default)]
+struct NetworkState {
+    routes: Vec<(Route, Handler)>,
+    requests: Vec<MockRequest>,
+    unhandled: Vec<MockRequest>,
+    fail_on_unhandled: bool,
+}
+
+/// Answers the requests made while it's alive with in-page handlers, see the [module docs](self)
+///
+/// Its layer of `fetch` is removed when it's dropped, and the original `fetch` and `XMLHttpRequest`
+/// are restored once nothing else intercepts them
+pub struct MockNetwork {
+    state: Rc<RefCell<NetworkState>>,
+    _fetch: FetchLayerGuard,
+    _xhr: XhrGuard,
+}
+
+impl MockNetwork {
+    /// Starts mocking the network. Requests that no handler matches are passed on to what
+    /// intercepted `fetch` before (like other mocks), and go out as usual if nothing answers them,
+    /// unless [`Self::fail_on_unhandled`] is set
+    pub fn start() -> Self {
+        let state = Rc::new(RefCell::new(NetworkState::default()));
+
+        Self {
+            _fetch: push_fetch_layer(fetch_layer(Rc::clone(&state))),
+            _xhr: XhrGuard::new(),
+            state,
+        }
+    }
+
+    /// Answers the requests that match `route` with `handler`
+    ///
+    /// Handlers added later take precedence, so a test can override a default one
+    pub fn on(
+        &self,
+        route: Route,
+        handler: impl Fn(&MockRequest) -> MockResponse + 'static,
+    ) -> &Self {
+        self.state
+            .borrow_mut()
+            .routes
+            .push((route, Rc::new(handler)));
+        self
+    }
+
+    /// Makes requests that would go out fail instead, and fails the test when this is dropped if
+    /// there were any
+    ///
+    /// A request is only unhandled if neither this nor what intercepted `fetch` before answers it,
+    /// so e.g. the Leptos server function mocks created earlier keep working
+    pub fn fail_on_unhandled(&self) -> &Self {
+        self.state.borrow_mut().fail_on_unhandled = true;
+        self
+    }
+
+    /// Takes the requests that nothing answered so far, so they don't fail the test when this is
+    /// dropped
+    pub fn take_unhandled(&self) -> Vec<MockRequest> {
+        std::mem::take(&mut self.state.borrow_mut().unhandled)
+    }
+
+    /// Every request made so far, in order
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.state.borrow().requests.clone()
+    }
+
+    /// Asserts that some request was made with `method` to a URL matching `pattern` (see [`Route`]),
+    /// and if `body` is given, with that body
+    pub fn assert_requested(&self, method: &str, pattern: &str, body: Option<&str>) -> &Self {
+        let mut route = Route::new(method, pattern);
+        route.body = body.map(ToString::to_string);
+
+        let state = self.state.borrow();
+        assert!(
+            state.requests.iter().any(|request| route.matches(request)),
+            "no request matched {} {pattern}{}, requests:\n  - {}",
+            route.method,
+            body.map(|body| format!(" {body}")).unwrap_or_default(),
+            state
+                .requests
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n  - ")
+        );
+
+        self
+    }
+
+    /// Asserts that `times` requests were made with `method` to a URL matching `pattern`
+    pub fn assert_requested_times(&self, method: &str, pattern: &str, times: usize) -> &Self {
+        let route = Route::new(method, pattern);
+        let count = self
+            .state
+            .borrow()
+            .requests
+            .iter()
+            .filter(|request| route.matches(request))
+            .count();
+
+        assert_eq!(
+            count, times,
+            "{} {pattern} was requested an unexpected number of times",
+            route.method
+        );
+
+        self
+    }
+}
+
+impl Drop for MockNetwork {
+    fn drop(&mut self) {
+        let state = self.state.borrow();
+        if state.fail_on_unhandled && !state.unhandled.is_empty() && !std::thread::panicking() {
+            panic!(
+                "some requests weren't handled by any mock:\n  - {}",
+                state
+                    .unhandled
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("\n  - ")
+            );
+        }
+    }
+}
+
+fn fetch_layer(state: Rc<RefCell<NetworkState>>) -> FetchLayer {
+    Rc::new(move |input: JsValue, init: JsValue, next: Next| {
+        let state = Rc::clone(&state);
+
+        wasm_bindgen_futures::future_to_promise(async move {
+            let request = MockRequest::read(&input, &init).await?;
+
+            let handler = {
+                let mut state = state.borrow_mut();
+                state.requests.push(request.clone());
+                state
+                    .routes
+                    .iter()
+                    .rev()
+                    .find(|(route, _)| route.matches(&request))
+                    .map(|(_, handler)| Rc::clone(handler))
+            };
+            if let Some(handler) = handler {
+                return handler(&request).into_js().await;
+            }
+
+            let next = next.or_else(move |input, init, next| {
+                let fail_on_unhandled = {
+                    let mut state = state.borrow_mut();
+                    state.unhandled.push(request.clone());
+                    state.fail_on_unhandled
+                };
+
+                if fail_on_unhandled {
+                    js_sys::Promise::reject(
+                        &js_sys::TypeError::new(&format!("no mock handles {request}")).into(),
+                    )
+                } else {
+                    next.fetch(input, init)
+                }
+            });
+            JsFuture::from(next.fetch(input, init)).await
+        })
+    })
+}
+
+fn url_matches(pattern: &str, request: &MockRequest) -> bool {
+    let target = if pattern.starts_with("http") {
+        request
+            .url
+            .split('?')
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    } else {
+        request.path.clone()
+    };
+
+    if pattern.contains('?') {
+        glob_matches(pattern, &format!("{target}{}", request.query))
+    } else {
+        glob_matches(pattern, &target)
+    }
+}
+
+/// Matches `text` against a pattern where `*` matches anything and `:name` matches up to the next `/`
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    match pattern.chars().next() {
+        None => text.is_empty(),
+        Some('*') => {
+            let rest = &pattern[1..];
+            (0..=text.len())
+                .filter(|&i| text.is_char_boundary(i))
+                .any(|i| glob_matches(rest, &text[i..]))
+        }
+        Some(':') => {
+            let name_len = pattern[1..]
+                .find(|c: char| !c.is_alphanumeric() && c != '_')
+                .unwrap_or(pattern.len() - 1);
+            let rest = &pattern[1 + name_len..];
+            let segment_len = text.find(['/', '?']).unwrap_or(text.len());
+            segment_len > 0 && glob_matches(rest, &text[segment_len..])
+        }
+        Some(c) => text
+            .strip_prefix(c)
+            .is_some_and(|text| glob_matches(&pattern[c.len_utf8()..], text)),
+    }
+}
+
+fn bodies_match(expected: &str, actual: &str) -> bool {
+    match (
+        serde_json::from_str::<serde_json::Value>(expected),
+        serde_json::from_str::<serde_json::Value>(actual),
+    ) {
+        (Ok(expected), Ok(actual)) => expected == actual,
+        _ => expected == actual,
+    }
+}
+
+#[cfg(all(test, target_family = "wasm"))]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    async fn fetch_text(url: &str, init: &web_sys::RequestInit) -> (u16, String) {
+        let window = web_sys::window().unwrap();
+        let response: web_sys::Response = JsFuture::from(window.fetch_with_str_and_init(url, init))
+            .await
+            .unwrap()
+            .unchecked_into();
+        let text = JsFuture::from(response.text().unwrap()).await.unwrap();
+        (response.status(), text.as_string().unwrap())
+    }
+
+    #[wasm_bindgen_test]
+    async fn answers_fetch_with_handlers() {
+        let network = MockNetwork::start();
+        network
+            .on(Route::get("/api/items/:id"), |request| {
+                MockResponse::json(&[request.path()])
+            })
+            .on(
+                Route::post("/api/items").with_json_body(&serde_json::json!({ "title": "milk" })),
+                |_| MockResponse::status(201),
+            );
+
+        let (status, body) = fetch_text("/api/items/1", &web_sys::RequestInit::new()).await;
+        assert_eq!((status, body.as_str()), (200, r#"["/api/items/1"]"#));
+
+        let init = web_sys::RequestInit::new();
+        init.set_method("POST");
+        init.set_body(&JsValue::from_str(r#"{ "title": "milk" }"#));
+        let (status, _) = fetch_text("/api/items", &init).await;
+        assert_eq!(status, 201);
+
+        network
+            .assert_requested("POST", "/api/items", Some(r#"{"title":"milk"}"#))
+            .assert_requested_times("GET", "/api/*", 1);
+    }
+
+    #[wasm_bindgen_test]
+    async fn answers_xhr_with_handlers() {
+        let network = MockNetwork::start();
+        network.on(Route::get("/api/greeting"), |_| MockResponse::text("hi"));
+
+        let xhr = web_sys::XmlHttpRequest::new().unwrap();
+        let loaded = js_sys::Promise::new(&mut |resolve, _| {
+            xhr.set_onloadend(Some(&resolve));
+        });
+        xhr.open("GET", "/api/greeting").unwrap();
+        xhr.send().unwrap();
+        JsFuture::from(loaded).await.unwrap();
+
+        assert_eq!(xhr.status().unwrap(), 200);
+        assert_eq!(xhr.response_text().unwrap().as_deref(), Some("hi"));
+        network.assert_requested("GET", "/api/greeting", None);
+    }
+
+    #[wasm_bindgen_test]
+    async fn rejects_unhandled_requests_when_asked() {
+        let network = MockNetwork::start();
+        network.fail_on_unhandled();
+
+        let window = web_sys::window().unwrap();
+        let result = JsFuture::from(window.fetch_with_str("/api/unknown")).await;
+        assert!(result.is_err());
+
+        // it would fail the test when dropped otherwise
+        let unhandled = network.take_unhandled();
+        assert_eq!(unhandled.len(), 1);
+        assert_eq!(unhandled[0].path, "/api/unknown");
+    }
+
+    #[wasm_bindgen_test]
+    async fn passes_unhandled_requests_to_lower_layers() {
+        let lower = MockNetwork::start();
+        lower.on(Route::get("/api/lower"), |_| MockResponse::text("lower"));
+        let upper = MockNetwork::start();
+        upper.fail_on_unhandled();
+
+        let (_, body) = fetch_text("/api/lower", &web_sys::RequestInit::new()).await;
+        assert_eq!(body, "lower");
+        assert!(upper.take_unhandled().is_empty());
+        upper.assert_requested("GET", "/api/lower", None);
+    }
+
+    #[wasm_bindgen_test]
+    async fn layers_can_be_dropped_in_any_order() {
+        let global = js_sys::global();
+        let original = js_sys::Reflect::get(&global, &"fetch".into()).unwrap();
+
+        let outer = MockNetwork::start();
+        outer.on(Route::get("/api/outer"), |_| MockResponse::text("outer"));
+        let inner = MockNetwork::start();
+        inner.on(Route::get("/api/inner"), |_| MockResponse::text("inner"));
+
+        let (_, body) = fetch_text("/api/outer", &web_sys::RequestInit::new()).await;
+        assert_eq!(body, "outer");
+
+        drop(outer);
+        let (_, body) = fetch_text("/api/inner", &web_sys::RequestInit::new()).await;
+        assert_eq!(body, "inner");
+
+        drop(inner);
+        assert_eq!(
+            js_sys::Reflect::get(&global, &"fetch".into()).unwrap(),
+            original
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn matches_url_patterns() {
+        assert!(glob_matches("/api/items/:id", "/api/items/12"));
+        assert!(!glob_matches("/api/items/:id", "/api/items/12/tags"));
+        assert!(glob_matches("/api/*", "/api/items/12/tags"));
+        assert!(!glob_matches("/api/items", "/api/items/12"));
+    }
+}
@@ -0,0 +1,571 @@
+//! Controllable fakes for browser APIs that depend on layout or on the environment
+//!
+//! Each fake replaces its global while it's alive, and nothing happens until the test triggers it:
+//!
+//! ```ignore
+//! let intersection = FakeIntersectionObserver::install();
+//! let wrapper = mount_test(|| view! { <LazyList /> });
+//!
+//! intersection.enter(&wrapper.query("#load-more").assert_exists()).await;
+//! assert_eq!(wrapper.query_all("li").len(), 40);
+//! ```
+//!
+//! Unlike the real observers, the fake ones don't report the initial state of an element when they
+//! start observing it.
+
+use wasm_bindgen::{JsCast as _, JsValue};
+
+use crate::{
+    framework::Framework,
+    wrapper::{Single, TestWrapper, TestWrapperState},
+};
+
+/// Builds the fake `IntersectionObserver` class, which adds its instances to `instances`
+const FAKE_INTERSECTION_OBSERVER: &str = r#"
+return class IntersectionObserver {
+    constructor(callback, options = {}) {
+        const threshold = options.threshold ?? 0;
+        this.root = options.root ?? null;
+        this.rootMargin = options.rootMargin ?? "0px";
+        this.thresholds = Array.isArray(threshold) ? threshold : [threshold];
+        this._callback = callback;
+        this._targets = new Set();
+        instances.add(this);
+    }
+
+    observe(target) {
+        this._targets.add(target);
+    }
+
+    unobserve(target) {
+        this._targets.delete(target);
+    }
+
+    disconnect() {
+        this._targets.clear();
+    }
+
+    takeRecords() {
+        return [];
+    }
+};
+"#;
+
+/// Builds the fake `ResizeObserver` class, which adds its instances to `instances`
+const FAKE_RESIZE_OBSERVER: &str = r#"
+return class ResizeObserver {
+    constructor(callback) {
+        this._callback = callback;
+        this._targets = new Set();
+        instances.add(this);
+    }
+
+    observe(target) {
+        this._targets.add(target);
+    }
+
+    unobserve(target) {
+        this._targets.delete(target);
+    }
+
+    disconnect() {
+        this._targets.clear();
+    }
+};
+"#;
+
+/// Calls every observer in `instances` that observes `target` with `entry`, returns how many
+const NOTIFY_OBSERVERS: &str = r#"
+let notified = 0;
+for (const observer of instances) {
+    if (observer._targets.has(target)) {
+        observer._callback.call(observer, [entry], observer);
+        notified += 1;
+    }
+}
+return notified;
+"#;
+
+/// Builds the fake `matchMedia`. Queries set in `values` match as set there, and the rest as they
+/// would with the real `matchMedia`
+const FAKE_MATCH_MEDIA: &str = r#"
+class MediaQueryList extends EventTarget {
+    constructor(media) {
+        super();
+        this.media = media;
+        this.onchange = null;
+        this._matches = this.matches;
+        lists.push(new WeakRef(this));
+    }
+
+    get matches() {
+        const key = normalize(this.media);
+        return values.has(key) ? values.get(key) : originalMatchMedia(this.media).matches;
+    }
+
+    addListener(listener) {
+        this.addEventListener("change", listener);
+    }
+
+    removeListener(listener) {
+        this.removeEventListener("change", listener);
+    }
+
+    dispatchEvent(event) {
+        const result = super.dispatchEvent(event);
+        if (typeof this.onchange === "function") {
+            this.onchange.call(this, event);
+        }
+        return result;
+    }
+}
+
+return function matchMedia(media) {
+    return new MediaQueryList(String(media));
+};
+"#;
+
+/// Makes queries that only differ in case or spacing equal
+const NORMALIZE_MEDIA: &str = r#"
+return String(media).trim().toLowerCase().replace(/\s+/g, " ").replace(/\s*([:(),])\s*/g, "$1");
+"#;
+
+/// Sets `key` in `values` and fires `change` on the lists whose result changed
+const SET_MEDIA: &str = r#"
+values.set(normalize(media), matches);
+for (const ref of lists) {
+    const list = ref.deref();
+    if (list && list.matches !== list._matches) {
+        list._matches = list.matches;
+        list.dispatchEvent(new MediaQueryListEvent("change", { media: list.media, matches: list.matches }));
+    }
+}
+"#;
+
+/// Replaces `globalThis[name]` with `fake` and returns what was there
+fn replace_global(name: &str, fake: &JsValue) -> JsValue {
+    let global = js_sys::global();
+    let original = js_sys::Reflect::get(&global, &name.into()).unwrap();
+    js_sys::Reflect::set(&global, &name.into(), fake).unwrap();
+    original
+}
+
+fn restore_global(name: &str, original: &JsValue) {
+    js_sys::Reflect::set(&js_sys::global(), &name.into(), original).unwrap();
+}
+
+/// Calls the observers in `instances` watching `target` with `entry`, panics if there are none
+fn notify(instances: &js_sys::Set, target: &web_sys::Element, entry: &js_sys::Object, api: &str) {
+    let notify = js_sys::Function::new_with_args("instances, target, entry", NOTIFY_OBSERVERS);
+    let notified = notify
+        .call3(&JsValue::NULL, instances, target, entry)
+        .unwrap_or_else(|err| panic!("an {api} callback threw: {err:?}"));
+
+    assert!(
+        notified.as_f64().unwrap_or_default() > 0.0,
+        "no {api} is observing the element: {}",
+        target.outer_html()
+    );
+}
+
+fn object(entries: &[(&str, JsValue)]) -> js_sys::Object {
+    let object = js_sys::Object::new();
+    for (key, value) in entries {
+        js_sys::Reflect::set(&object, &(*key).into(), value).unwrap();
+    }
+    object
+}
+
+fn dom_rect(x: f64, y: f64, width: f64, height: f64) -> JsValue {
+    let dom_rect = js_sys::Reflect::get(&js_sys::global(), &"DOMRectReadOnly".into()).unwrap();
+    let args = js_sys::Array::of4(&x.into(), &y.into(), &width.into(), &height.into());
+    js_sys::Reflect::construct(dom_rect.unchecked_ref::<js_sys::Function>(), &args).unwrap()
+}
+
+/// A fake `IntersectionObserver`, installed until this is dropped. See the [module docs](self)
+pub struct FakeIntersectionObserver {
+    instances: js_sys::Set,
+    original: JsValue,
+}
+
+impl FakeIntersectionObserver {
+    pub fn install() -> Self {
+        let instances = js_sys::Set::new(&JsValue::UNDEFINED);
+        let fake = js_sys::Function::new_with_args("instances", FAKE_INTERSECTION_OBSERVER)
+            .call1(&JsValue::NULL, &instances)
+            .unwrap();
+
+        Self {
+            original: replace_global("IntersectionObserver", &fake),
+            instances,
+        }
+    }
+
+    /// Reports the element as fully visible to the observers watching it, then settles
+    pub async fn enter<E, Fw>(&self, wrapper: &TestWrapper<Single<E>, Fw>) -> &Self
+    where
+        E: AsRef<web_sys::Element>,
+        Fw: Framework,
+    {
+        self.set_ratio(wrapper, 1.0).await
+    }
+
+    /// Reports the element as not visible at all to the observers watching it, then settles
+    pub async fn leave<E, Fw>(&self, wrapper: &TestWrapper<Single<E>, Fw>) -> &Self
+    where
+        E: AsRef<web_sys::Element>,
+        Fw: Framework,
+    {
+        self.set_ratio(wrapper, 0.0).await
+    }
+
+    /// Reports that `ratio` (from 0 to 1) of the element is visible to the observers watching it,
+    /// then settles
+    pub async fn set_ratio<E, Fw>(&self, wrapper: &TestWrapper<Single<E>, Fw>, ratio: f64) -> &Self
+    where
+        E: AsRef<web_sys::Element>,
+        Fw: Framework,
+    {
+        let target: &web_sys::Element = (**wrapper).as_ref();
+        let rect = target.get_bounding_client_rect();
+        let visible_height = rect.height() * ratio;
+        let entry = object(&[
+            ("target", target.into()),
+            ("isIntersecting", (ratio > 0.0).into()),
+            ("intersectionRatio", ratio.into()),
+            ("boundingClientRect", rect.clone().into()),
+            (
+                "intersectionRect",
+                dom_rect(rect.x(), rect.y(), rect.width(), visible_height),
+            ),
+            ("rootBounds", JsValue::NULL),
+            (
+                "time",
+                web_sys::window()
+                    .unwrap()
+                    .performance()
+                    .unwrap()
+                    .now()
+                    .into(),
+            ),
+        ]);
+
+        notify(&self.instances, target, &entry, "IntersectionObserver");
+        wrapper.settle().await;
+
+        self
+    }
+}
+
+impl Drop for FakeIntersectionObserver {
+    fn drop(&mut self) {
+        restore_global("IntersectionObserver", &self.original);
+    }
+}
+
+/// What [`fake_size`] defines on an element
+const FAKE_SIZE_PROPERTIES: &[&str] = &[
+    "getBoundingClientRect",
+    "clientWidth",
+    "offsetWidth",
+    "scrollWidth",
+    "clientHeight",
+    "offsetHeight",
+    "scrollHeight",
+];
+
+/// A fake `ResizeObserver`, installed until this is dropped. See the [module docs](self)
+pub struct FakeResizeObserver {
+    instances: js_sys::Set,
+    /// The elements given a size, which measure normally again once this is dropped
+    sized: js_sys::Set,
+    original: JsValue,
+}
+
+impl FakeResizeObserver {
+    pub fn install() -> Self {
+        let instances = js_sys::Set::new(&JsValue::UNDEFINED);
+        let fake = js_sys::Function::new_with_args("instances", FAKE_RESIZE_OBSERVER)
+            .call1(&JsValue::NULL, &instances)
+            .unwrap();
+
+        Self {
+            original: replace_global("ResizeObserver", &fake),
+            instances,
+            sized: js_sys::Set::new(&JsValue::UNDEFINED),
+        }
+    }
+
+    /// Reports the element as resized to `width`x`height` to the observers watching it, then settles
+    ///
+    /// The element also reports that size from then on when it's measured, through
+    /// `getBoundingClientRect()`, `clientWidth`, `offsetWidth` and so on, until this is dropped
+    pub async fn set_size<E, Fw>(
+        &self,
+        wrapper: &TestWrapper<Single<E>, Fw>,
+        width: f64,
+        height: f64,
+    ) -> &Self
+    where
+        E: AsRef<web_sys::Element>,
+        Fw: Framework,
+    {
+        let target: &web_sys::Element = (**wrapper).as_ref();
+        fake_size(target, width, height);
+        self.sized.add(target);
+
+        let size = object(&[("inlineSize", width.into()), ("blockSize", height.into())]);
+        let sizes: JsValue = js_sys::Array::of1(&size).into();
+        let entry = object(&[
+            ("target", target.into()),
+            ("contentRect", dom_rect(0.0, 0.0, width, height)),
+            ("borderBoxSize", sizes.clone()),
+            ("contentBoxSize", sizes.clone()),
+            ("devicePixelContentBoxSize", sizes),
+        ]);
+
+        notify(&self.instances, target, &entry, "ResizeObserver");
+        wrapper.settle().await;
+
+        self
+    }
+}
+
+impl Drop for FakeResizeObserver {
+    fn drop(&mut self) {
+        restore_global("ResizeObserver", &self.original);
+
+        self.sized.for_each(&mut |elem, _, _| {
+            for name in FAKE_SIZE_PROPERTIES {
+                js_sys::Reflect::delete_property(
+                    elem.unchecked_ref::<js_sys::Object>(),
+                    &(*name).into(),
+                )
+                .unwrap();
+            }
+        });
+    }
+}
+
+/// Makes `elem` report the given size when it's measured, by defining [`FAKE_SIZE_PROPERTIES`] on
+/// it
+fn fake_size(elem: &web_sys::Element, width: f64, height: f64) {
+    let define = js_sys::Function::new_with_args(
+        "elem, width, height",
+        "const rect = elem.getBoundingClientRect();
+        const fakeRect = new DOMRect(rect.x, rect.y, width, height);
+        Object.defineProperty(elem, 'getBoundingClientRect', {
+            value: () => DOMRect.fromRect(fakeRect),
+            configurable: true,
+        });
+        for (const name of ['clientWidth', 'offsetWidth', 'scrollWidth']) {
+            Object.defineProperty(elem, name, { get: () => width, configurable: true });
+        }
+        for (const name of ['clientHeight', 'offsetHeight', 'scrollHeight']) {
+            Object.defineProperty(elem, name, { get: () => height, configurable: true });
+        }",
+    );
+    define
+        .call3(&JsValue::NULL, elem, &width.into(), &height.into())
+        .unwrap();
+}
+
+/// A fake `window.matchMedia`, installed until this is dropped. See the [module docs](self)
+pub struct FakeMatchMedia {
+    values: js_sys::Map,
+    lists: js_sys::Array,
+    normalize: js_sys::Function,
+    original: JsValue,
+}
+
+impl FakeMatchMedia {
+    /// Replaces `matchMedia`. Every query matches as it would with the real one until it's [`set`]
+    ///
+    /// [`set`]: Self::set
+    pub fn install() -> Self {
+        let values = js_sys::Map::new();
+        let lists = js_sys::Array::new();
+        let normalize = js_sys::Function::new_with_args("media", NORMALIZE_MEDIA);
+        let original_match_media =
+            js_sys::Reflect::get(&js_sys::global(), &"matchMedia".into()).unwrap();
+        let original_match_media = original_match_media
+            .unchecked_ref::<js_sys::Function>()
+            .bind0(&js_sys::global());
+
+        let fake = js_sys::Function::new_with_args(
+            "values, lists, normalize, originalMatchMedia",
+            FAKE_MATCH_MEDIA,
+        );
+        let args = js_sys::Array::of4(&values, &lists, &normalize, &original_match_media);
+        let fake = fake.apply(&JsValue::NULL, &args).unwrap();
+
+        Self {
+            original: replace_global("matchMedia", &fake),
+            values,
+            lists,
+            normalize,
+        }
+    }
+
+    /// Makes `query` match or not from now on, firing `change` on the lists for it whose result
+    /// changes, then settles `wrapper`
+    pub async fn set<State, Fw>(
+        &self,
+        wrapper: &TestWrapper<State, Fw>,
+        query: &str,
+        matches: bool,
+    ) -> &Self
+    where
+        State: TestWrapperState,
+        Fw: Framework,
+    {
+        let set =
+            js_sys::Function::new_with_args("values, lists, normalize, media, matches", SET_MEDIA);
+        let args = js_sys::Array::of5(
+            &self.values,
+            &self.lists,
+            &self.normalize,
+            &query.into(),
+            &matches.into(),
+        );
+        set.apply(&JsValue::NULL, &args)
+            .unwrap_or_else(|err| panic!("a matchMedia listener threw: {err:?}"));
+
+        wrapper.settle().await;
+
+        self
+    }
+}
+
+impl Drop for FakeMatchMedia {
+    fn drop(&mut self) {
+        restore_global("matchMedia", &self.original);
+    }
+}
+
+#[cfg(all(test, target_family = "wasm"))]
+mod tests {
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    };
+
+    use super::*;
+    use crate::{BaseTestWrapper, Vanilla};
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    /// Creates a JS callback that stores the first argument it's called with
+    fn recorder() -> (Rc<RefCell<JsValue>>, JsValue) {
+        let last = Rc::new(RefCell::new(JsValue::UNDEFINED));
+        let last_clone = Rc::clone(&last);
+        let callback = Closure::<dyn Fn(JsValue)>::new(move |arg| *last_clone.borrow_mut() = arg);
+        (last, callback.into_js_value())
+    }
+
+    fn get(value: &JsValue, path: &[&str]) -> JsValue {
+        path.iter().fold(value.clone(), |value, key| {
+            js_sys::Reflect::get(&value, &(*key).into()).unwrap()
+        })
+    }
+
+    fn construct(class: &str, callback: &JsValue) -> JsValue {
+        let class = js_sys::Reflect::get(&js_sys::global(), &class.into()).unwrap();
+        js_sys::Reflect::construct(
+            class.unchecked_ref::<js_sys::Function>(),
+            &js_sys::Array::of1(callback),
+        )
+        .unwrap()
+    }
+
+    fn observe(observer: &JsValue, target: &web_sys::Element) {
+        let observe: js_sys::Function = get(observer, &["observe"]).unchecked_into();
+        observe.call1(observer, target).unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    async fn triggers_intersections() {
+        let intersection = FakeIntersectionObserver::install();
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html("<img>");
+        let img = wrapper.query("img").assert_exists();
+
+        let (entries, callback) = recorder();
+        observe(&construct("IntersectionObserver", &callback), &img);
+
+        intersection.enter(&img).await;
+        assert_eq!(get(&entries.borrow(), &["0", "isIntersecting"]), true);
+
+        intersection.leave(&img).await;
+        assert_eq!(get(&entries.borrow(), &["0", "intersectionRatio"]), 0.0);
+    }
+
+    #[wasm_bindgen_test]
+    #[should_panic = "no IntersectionObserver is observing the element"]
+    async fn fails_on_unobserved_elements() {
+        let intersection = FakeIntersectionObserver::install();
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html("<img>");
+
+        intersection
+            .enter(&wrapper.query("img").assert_exists())
+            .await;
+    }
+
+    #[wasm_bindgen_test]
+    async fn triggers_resizes() {
+        let resize = FakeResizeObserver::install();
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html("<div></div>");
+        let div = wrapper.query("div").assert_exists();
+
+        let (entries, callback) = recorder();
+        observe(&construct("ResizeObserver", &callback), &div);
+
+        resize.set_size(&div, 320.0, 200.0).await;
+        assert_eq!(
+            get(&entries.borrow(), &["0", "contentRect", "width"]),
+            320.0
+        );
+        assert_eq!(div.client_height(), 200);
+        assert_eq!(div.get_bounding_client_rect().width(), 320.0);
+
+        drop(resize);
+        assert_eq!(div.client_height(), 0);
+    }
+
+    #[wasm_bindgen_test]
+    async fn changes_media_queries() {
+        let media = FakeMatchMedia::install();
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html("");
+        let window = web_sys::window().unwrap();
+        let dark_mode = window
+            .match_media("(prefers-color-scheme: dark)")
+            .unwrap()
+            .unwrap();
+
+        let changes = Rc::new(Cell::new(0));
+        let changes_clone = Rc::clone(&changes);
+        let on_change =
+            Closure::<dyn Fn()>::new(move || changes_clone.set(changes_clone.get() + 1));
+        dark_mode
+            .add_event_listener_with_callback("change", on_change.as_ref().unchecked_ref())
+            .unwrap();
+
+        media
+            .set(&wrapper, "(prefers-color-scheme:dark)", true)
+            .await;
+        assert!(dark_mode.matches());
+        assert_eq!(changes.get(), 1);
+
+        media
+            .set(&wrapper, "(prefers-color-scheme: dark)", true)
+            .await;
+        assert_eq!(changes.get(), 1);
+
+        media
+            .set(&wrapper, "(prefers-color-scheme: dark)", false)
+            .await;
+        assert!(!dark_mode.matches());
+        assert_eq!(changes.get(), 2);
+    }
+}
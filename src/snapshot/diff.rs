@@ -0,0 +1,53 @@
+/// A minimal line diff (LCS-based) for showing snapshot mismatches
+///
+/// Lines only in `old` are prefixed with `-`, lines only in `new` with `+`
+pub(crate) fn line_diff(old: &str, new: &str) -> String {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+
+    // lcs[i][j] is the length of the longest common subsequence of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut res = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            res.push(format!("  {}", old[i]));
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            res.push(format!("+ {}", new[j]));
+            j += 1;
+        } else {
+            res.push(format!("- {}", old[i]));
+            i += 1;
+        }
+    }
+
+    res.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_lines() {
+        let old = "<div>\n  <span>old</span>\n</div>";
+        let new = "<div>\n  <span>new</span>\n  <p>added</p>\n</div>";
+
+        assert_eq!(
+            line_diff(old, new),
+            "  <div>\n+   <span>new</span>\n+   <p>added</p>\n-   <span>old</span>\n  </div>"
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//! Snapshot testing of the DOM
+//!
+//! Elements are serialized to a normalized, pretty-printed HTML (see [`SnapshotOptions`]) and compared
+//! against a stored value, either inline in the source or in a file loaded at compile time with
+//! [`assert_snapshot!`](crate::assert_snapshot).
+//!
+//! Set the `DOM_TEST_LIB_UPDATE_SNAPSHOTS` environment variable when building the tests to print the
+//! new value of mismatched snapshots instead of failing.
+
+mod diff;
+mod serialize;
+
+use std::rc::Rc;
+
+pub(crate) use diff::line_diff;
+pub use serialize::{serialize, serialize_element};
+
+/// Attributes that are removed from every snapshot by default, since they're generated by frameworks
+/// and have no meaning for the test
+const DEFAULT_REMOVED_ATTRS: &[&str] = &["data-hk"];
+
+/// A rule for rewriting an attribute: returns `None` to remove it or `Some(value)` to replace its value
+type AttrRule = Rc<dyn Fn(&str, &str) -> Option<String>>;
+/// A rule for rewriting a text node
+type TextRule = Rc<dyn Fn(&str) -> String>;
+
+/// Controls how the DOM is normalized into a snapshot
+#[derive(Clone)]
+pub struct SnapshotOptions {
+    keep_comments: bool,
+    attr_rules: Vec<AttrRule>,
+    text_rules: Vec<TextRule>,
+}
+
+impl Default for SnapshotOptions {
+    fn default() -> Self {
+        Self {
+            keep_comments: false,
+            attr_rules: Vec::new(),
+            text_rules: Vec::new(),
+        }
+        .remove_attrs(DEFAULT_REMOVED_ATTRS)
+    }
+}
+
+impl SnapshotOptions {
+    /// Keep comment nodes in the snapshot. By default they're stripped since frameworks like Leptos
+    /// use them as hydration markers
+    pub fn keep_comments(mut self) -> Self {
+        self.keep_comments = true;
+        self
+    }
+
+    /// Removes the given attributes from every element
+    pub fn remove_attrs(self, names: &[&str]) -> Self {
+        let names = names.iter().map(ToString::to_string).collect::<Vec<_>>();
+        self.attr_rule(move |name, value| {
+            (!names.iter().any(|removed| removed == name)).then(|| value.to_string())
+        })
+    }
+
+    /// Replaces the value of the given attribute with `[redacted]`, useful for generated ids
+    pub fn redact_attr(self, name: &str) -> Self {
+        let name = name.to_string();
+        self.attr_rule(move |attr_name, value| {
+            Some(if attr_name == name {
+                String::from("[redacted]")
+            } else {
+                value.to_string()
+            })
+        })
+    }
+
+    /// Adds a custom rule for attributes. It receives the attribute's name and value and returns
+    /// `None` to remove it or `Some(new_value)` to keep it
+    pub fn attr_rule(mut self, rule: impl Fn(&str, &str) -> Option<String> + 'static) -> Self {
+        self.attr_rules.push(Rc::new(rule));
+        self
+    }
+
+    /// Adds a custom rule to rewrite text nodes
+    pub fn text_rule(mut self, rule: impl Fn(&str) -> String + 'static) -> Self {
+        self.text_rules.push(Rc::new(rule));
+        self
+    }
+}
+
+/// A serialized snapshot of some element, ready to be compared
+pub struct Snapshot {
+    /// Used to identify the snapshot in error messages
+    pub name: String,
+    pub content: String,
+}
+
+impl Snapshot {
+    /// Asserts that this snapshot equals the expected value.
+    ///
+    /// The expected value is dedented and trimmed first, so it can be comfortably written inline
+    pub fn assert_matches(&self, expected: &str) {
+        let expected = normalize_expected(expected);
+        if expected == self.content {
+            return;
+        }
+
+        if option_env!("DOM_TEST_LIB_UPDATE_SNAPSHOTS").is_some() {
+            wasm_bindgen_test::console_log!(
+                "snapshot `{}` changed, new value:\n{}",
+                self.name,
+                self.content
+            );
+            return;
+        }
+
+        panic!(
+            "snapshot `{}` does not match:\n{}\nnew value:\n{}",
+            self.name,
+            diff::line_diff(&expected, &self.content),
+            self.content
+        );
+    }
+}
+
+impl std::fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.content)
+    }
+}
+
+/// Removes the indentation common to all lines and any surrounding blank lines
+fn normalize_expected(expected: &str) -> String {
+    let lines = expected
+        .lines()
+        .map(str::trim_end)
+        .skip_while(|line| line.is_empty())
+        .collect::<Vec<_>>();
+    let lines = &lines[..lines
+        .iter()
+        .rposition(|line| !line.is_empty())
+        .map_or(0, |i| i + 1)];
+    let indent = lines
+        .iter()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Asserts that the snapshot of a wrapper matches a stored value
+///
+/// * `assert_snapshot!(wrapper, @"<p>inline</p>")` compares against an inline snapshot
+/// * `assert_snapshot!(wrapper, "snapshots/file.html")` compares against a file, loaded at compile time
+///   relative to the current source file
+/// * `assert_snapshot!(wrapper)` has no stored value yet, so it fails printing the current one
+///
+/// The wrapper can be a base wrapper (snapshots the whole root) or a single element
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($wrapper:expr, @$expected:literal) => {
+        $wrapper
+            .snapshot(concat!(file!(), ":", line!()))
+            .assert_matches($expected)
+    };
+    ($wrapper:expr, $path:literal) => {
+        $wrapper.snapshot($path).assert_matches(include_str!($path))
+    };
+    ($wrapper:expr) => {
+        $wrapper
+            .snapshot(concat!(file!(), ":", line!()))
+            .assert_matches("")
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_expected() {
+        let expected = "
+            <div>
+              <span>hi</span>
+            </div>
+        ";
+
+        assert_eq!(
+            normalize_expected(expected),
+            "<div>\n  <span>hi</span>\n</div>"
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use super::SnapshotOptions;
+
+/// Elements that can't have children and thus are not closed
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+const INDENT: &str = "  ";
+
+/// Serializes the children of the given node into a deterministic, pretty-printed HTML
+///
+/// Attributes are sorted, whitespace in text nodes is collapsed and empty text is removed, so the result
+/// only changes when the DOM meaningfully does
+pub fn serialize(root: &web_sys::Node, options: &SnapshotOptions) -> String {
+    let mut lines = Vec::new();
+    for child in children(root, options) {
+        serialize_node(&child, options, 0, &mut lines);
+    }
+    lines.join("\n")
+}
+
+/// Serializes an element itself (not only its children) into a pretty-printed HTML
+pub fn serialize_element(elem: &web_sys::Element, options: &SnapshotOptions) -> String {
+    let mut lines = Vec::new();
+    serialize_node(elem.as_ref(), options, 0, &mut lines);
+    lines.join("\n")
+}
+
+fn serialize_node(
+    node: &web_sys::Node,
+    options: &SnapshotOptions,
+    depth: usize,
+    lines: &mut Vec<String>,
+) {
+    use wasm_bindgen::JsCast as _;
+
+    let indent = INDENT.repeat(depth);
+    match node.node_type() {
+        web_sys::Node::ELEMENT_NODE => {
+            let elem = node.unchecked_ref::<web_sys::Element>();
+            let tag = elem.local_name();
+            let open_tag = format!("<{tag}{}>", attributes(elem, options));
+
+            if VOID_ELEMENTS.contains(&tag.as_str()) {
+                lines.push(format!("{indent}{open_tag}"));
+                return;
+            }
+
+            let children = children(node, options);
+            match children.as_slice() {
+                [] => lines.push(format!("{indent}{open_tag}</{tag}>")),
+                [only] if only.node_type() == web_sys::Node::TEXT_NODE => {
+                    lines.push(format!("{indent}{open_tag}{}</{tag}>", text(only, options)))
+                }
+                _ => {
+                    lines.push(format!("{indent}{open_tag}"));
+                    for child in &children {
+                        serialize_node(child, options, depth + 1, lines);
+                    }
+                    lines.push(format!("{indent}</{tag}>"));
+                }
+            }
+        }
+        web_sys::Node::TEXT_NODE => lines.push(format!("{indent}{}", text(node, options))),
+        web_sys::Node::COMMENT_NODE => lines.push(format!(
+            "{indent}<!--{}-->",
+            node.node_value().unwrap_or_default()
+        )),
+        _ => {}
+    }
+}
+
+/// The children of a node that will end up in the snapshot
+fn children(node: &web_sys::Node, options: &SnapshotOptions) -> Vec<web_sys::Node> {
+    use crate::util::NodeListExt as _;
+
+    node.child_nodes()
+        .into_iterator()
+        .filter(|child| match child.node_type() {
+            web_sys::Node::ELEMENT_NODE => true,
+            web_sys::Node::TEXT_NODE => !text(child, options).is_empty(),
+            web_sys::Node::COMMENT_NODE => options.keep_comments,
+            _ => false,
+        })
+        .collect()
+}
+
+/// Sorted and redacted attributes, with a leading space if there is any
+fn attributes(elem: &web_sys::Element, options: &SnapshotOptions) -> String {
+    let mut attrs = elem
+        .get_attribute_names()
+        .iter()
+        .filter_map(|name| name.as_string())
+        .filter_map(|name| {
+            let value = elem.get_attribute(&name).unwrap_or_default();
+            let value = options
+                .attr_rules
+                .iter()
+                .try_fold(value, |value, rule| rule(&name, &value))?;
+            Some((name, value))
+        })
+        .collect::<Vec<_>>();
+    attrs.sort();
+
+    attrs
+        .into_iter()
+        .map(|(name, value)| {
+            if value.is_empty() {
+                format!(" {name}")
+            } else {
+                format!(" {name}=\"{}\"", escape(&value).replace('"', "&quot;"))
+            }
+        })
+        .collect()
+}
+
+/// The text of a text node with its whitespace collapsed and the text rules applied
+fn text(node: &web_sys::Node, options: &SnapshotOptions) -> String {
+    let text = node
+        .text_content()
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let text = options
+        .text_rules
+        .iter()
+        .fold(text, |text, rule| rule(&text));
+    escape(&text)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,404 @@
+//! Sandboxing of `localStorage`, `sessionStorage` and cookies
+//!
+//! Every test runs in the same origin, so whatever a test persists is there for the next ones. A
+//! [`StorageSandbox`] starts from empty storage and puts back what was there before once it's
+//! dropped:
+//!
+//! ```ignore
+//! let storage = StorageSandbox::install();
+//! storage.seed_local("theme", &"dark");
+//!
+//! let wrapper = mount_test(|| view! { <ThemeToggle /> });
+//! wrapper.query_as_button("button").assert_exists().click().await;
+//!
+//! storage.assert_local_storage("theme", &"light");
+//! ```
+
+use serde::Serialize;
+use wasm_bindgen::JsCast as _;
+
+/// Which of the storages an operation is about
+#[derive(Debug, Clone, Copy)]
+enum Area {
+    Local,
+    Session,
+}
+
+impl Area {
+    fn storage(self) -> web_sys::Storage {
+        let window = web_sys::window().unwrap();
+        let storage = match self {
+            Area::Local => window.local_storage(),
+            Area::Session => window.session_storage(),
+        };
+        storage
+            .unwrap()
+            .unwrap_or_else(|| panic!("{} isn't available", self.name()))
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Area::Local => "localStorage",
+            Area::Session => "sessionStorage",
+        }
+    }
+
+    fn entries(self) -> Vec<(String, String)> {
+        let storage = self.storage();
+        (0..storage.length().unwrap())
+            .filter_map(|idx| storage.key(idx).unwrap())
+            .map(|key| {
+                let value = storage.get_item(&key).unwrap().unwrap_or_default();
+                (key, value)
+            })
+            .collect()
+    }
+
+    fn restore(self, entries: &[(String, String)]) {
+        let storage = self.storage();
+        storage.clear().unwrap();
+        for (key, value) in entries {
+            storage.set_item(key, value).unwrap();
+        }
+    }
+}
+
+fn document() -> web_sys::HtmlDocument {
+    web_sys::window()
+        .unwrap()
+        .document()
+        .unwrap()
+        .unchecked_into()
+}
+
+/// The cookies visible to the page, as `(name, value)`
+fn cookies() -> Vec<(String, String)> {
+    document()
+        .cookie()
+        .unwrap()
+        .split(';')
+        .filter(|cookie| !cookie.trim().is_empty())
+        .map(|cookie| match cookie.split_once('=') {
+            Some((name, value)) => (name.trim().to_string(), value.trim().to_string()),
+            None => (String::new(), cookie.trim().to_string()),
+        })
+        .collect()
+}
+
+fn set_cookie(cookie: &str) {
+    document().set_cookie(cookie).unwrap();
+}
+
+/// A cookie along with the attributes that remove it, which it has to be set with again to replace
+/// it
+struct Cookie {
+    name: String,
+    value: String,
+    scope: String,
+}
+
+/// The `path` and `domain` attributes the cookies visible to the page can have been set with
+///
+/// `document.cookie` doesn't tell, and a cookie can only be removed with the same ones
+fn cookie_scopes() -> Vec<String> {
+    let location = web_sys::window().unwrap().location();
+    let pathname = location.pathname().unwrap();
+    let hostname = location.hostname().unwrap();
+
+    let mut paths = vec!["/".to_string()];
+    let mut path = String::new();
+    for segment in pathname.split('/').filter(|segment| !segment.is_empty()) {
+        path = format!("{path}/{segment}");
+        paths.push(path.clone());
+        paths.push(format!("{path}/"));
+    }
+
+    // a cookie without a domain is only sent to the exact host, it's a different one from the same
+    // cookie with `domain` set to the host
+    let mut domains = vec![String::new()];
+    let labels = hostname.split('.').collect::<Vec<_>>();
+    for idx in 0..labels.len() {
+        domains.push(format!("; domain={}", labels[idx..].join(".")));
+    }
+
+    paths
+        .iter()
+        .flat_map(|path| {
+            domains
+                .iter()
+                .map(move |domain| format!("; path={path}{domain}"))
+        })
+        .collect()
+}
+
+/// Removes every cookie visible to the page and returns them, panics with the names of the ones
+/// that couldn't be removed
+fn clear_cookies() -> Vec<Cookie> {
+    let mut removed = Vec::new();
+
+    for scope in cookie_scopes() {
+        let mut names = cookies()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names.dedup();
+
+        for name in names {
+            // prefixed cookies can only be set, or removed, with `secure`
+            let scope = if name.starts_with("__Secure-") || name.starts_with("__Host-") {
+                format!("{scope}; secure")
+            } else {
+                scope.clone()
+            };
+
+            let mut before = cookies();
+            set_cookie(&format!(
+                "{name}=; expires=Thu, 01 Jan 1970 00:00:00 GMT{scope}"
+            ));
+            for cookie in cookies() {
+                if let Some(idx) = before.iter().position(|other| *other == cookie) {
+                    before.remove(idx);
+                }
+            }
+
+            removed.extend(before.into_iter().map(|(name, value)| Cookie {
+                name,
+                value,
+                scope: scope.clone(),
+            }));
+        }
+    }
+
+    let mut left = cookies()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect::<Vec<_>>();
+    left.sort_unstable();
+    left.dedup();
+    assert!(
+        left.is_empty(),
+        "couldn't clear the cookies {}",
+        left.iter()
+            .map(|name| format!("`{name}`"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    removed
+}
+
+/// Clears `localStorage` and `sessionStorage`
+pub(crate) fn clear_storage() {
+    Area::Local.storage().clear().unwrap();
+    Area::Session.storage().clear().unwrap();
+}
+
+/// Empty storage and cookies for a test, with the previous ones restored when it's dropped. See the
+/// [module docs](self)
+pub struct StorageSandbox {
+    local: Vec<(String, String)>,
+    session: Vec<(String, String)>,
+    cookies: Vec<Cookie>,
+}
+
+impl StorageSandbox {
+    /// Takes a snapshot of `localStorage`, `sessionStorage` and the cookies, then clears them
+    ///
+    /// Panics if some cookies can't be cleared
+    pub fn install() -> Self {
+        let local = Area::Local.entries();
+        let session = Area::Session.entries();
+        clear_storage();
+
+        Self {
+            local,
+            session,
+            cookies: clear_cookies(),
+        }
+    }
+
+    /// Stores `value` serialized as JSON in `localStorage`
+    pub fn seed_local(&self, key: &str, value: &impl Serialize) -> &Self {
+        self.seed(Area::Local, key, value)
+    }
+
+    /// Stores `value` serialized as JSON in `sessionStorage`
+    pub fn seed_session(&self, key: &str, value: &impl Serialize) -> &Self {
+        self.seed(Area::Session, key, value)
+    }
+
+    /// Sets a cookie, `value` may include attributes like `path=/` after a `;`
+    pub fn seed_cookie(&self, name: &str, value: &str) -> &Self {
+        set_cookie(&format!("{name}={value}"));
+        self
+    }
+
+    fn seed(&self, area: Area, key: &str, value: &impl Serialize) -> &Self {
+        area.storage()
+            .set_item(key, &serde_json::to_string(value).unwrap())
+            .unwrap();
+        self
+    }
+
+    /// The raw value stored in `localStorage` under `key`
+    pub fn local(&self, key: &str) -> Option<String> {
+        Area::Local.storage().get_item(key).unwrap()
+    }
+
+    /// The raw value stored in `sessionStorage` under `key`
+    pub fn session(&self, key: &str) -> Option<String> {
+        Area::Session.storage().get_item(key).unwrap()
+    }
+
+    /// The value of the cookie called `name`
+    pub fn cookie(&self, name: &str) -> Option<String> {
+        cookies()
+            .into_iter()
+            .find(|(cookie, _)| cookie == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Asserts that `localStorage` has `expected` under `key`. Both are compared as JSON values, so
+    /// formatting and the order of keys don't matter
+    pub fn assert_local_storage(&self, key: &str, expected: &impl Serialize) -> &Self {
+        self.assert_stored(Area::Local, key, expected)
+    }
+
+    /// Asserts that `sessionStorage` has `expected` under `key`, see [`Self::assert_local_storage`]
+    pub fn assert_session_storage(&self, key: &str, expected: &impl Serialize) -> &Self {
+        self.assert_stored(Area::Session, key, expected)
+    }
+
+    /// Asserts that there's nothing under `key` in `localStorage`
+    pub fn assert_local_storage_missing(&self, key: &str) -> &Self {
+        self.assert_missing(Area::Local, key)
+    }
+
+    /// Asserts that there's nothing under `key` in `sessionStorage`
+    pub fn assert_session_storage_missing(&self, key: &str) -> &Self {
+        self.assert_missing(Area::Session, key)
+    }
+
+    /// Asserts that the cookie called `name` has `expected` as its value, or doesn't exist if it's
+    /// `None`
+    pub fn assert_cookie(&self, name: &str, expected: Option<&str>) -> &Self {
+        assert_eq!(
+            self.cookie(name).as_deref(),
+            expected,
+            "unexpected value for cookie `{name}`"
+        );
+        self
+    }
+
+    fn assert_stored(&self, area: Area, key: &str, expected: &impl Serialize) -> &Self {
+        assert_stored_item(area, key, area.storage().get_item(key).unwrap(), expected);
+        self
+    }
+
+    fn assert_missing(&self, area: Area, key: &str) -> &Self {
+        let stored = area.storage().get_item(key).unwrap();
+        assert!(
+            stored.is_none(),
+            "{} has `{}` under `{key}`",
+            area.name(),
+            stored.unwrap_or_default()
+        );
+        self
+    }
+}
+
+/// Checks that `stored`, read from `area` under `key`, is `expected` serialized as JSON
+fn assert_stored_item(area: Area, key: &str, stored: Option<String>, expected: &impl Serialize) {
+    let expected = serde_json::to_value(expected).unwrap();
+    let stored = stored.unwrap_or_else(|| panic!("{} has nothing under `{key}`", area.name()));
+    let stored_value = serde_json::from_str::<serde_json::Value>(&stored).unwrap_or_else(|_| {
+        panic!(
+            "{} has `{stored}` under `{key}`, which isn't JSON",
+            area.name()
+        )
+    });
+
+    assert_eq!(
+        stored_value,
+        expected,
+        "unexpected value under `{key}` in {}",
+        area.name()
+    );
+}
+
+impl Drop for StorageSandbox {
+    fn drop(&mut self) {
+        Area::Local.restore(&self.local);
+        Area::Session.restore(&self.session);
+        if std::thread::panicking() {
+            // a cookie that can't be cleared would panic again
+            return;
+        }
+
+        clear_cookies();
+        for cookie in &self.cookies {
+            set_cookie(&format!("{}={}{}", cookie.name, cookie.value, cookie.scope));
+        }
+    }
+}
+
+#[cfg(all(test, target_family = "wasm"))]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn sandboxes_storage() {
+        Area::Local.storage().set_item("before", "1").unwrap();
+        set_cookie("before=1; path=/");
+
+        let storage = StorageSandbox::install();
+        storage
+            .assert_local_storage_missing("before")
+            .assert_cookie("before", None);
+
+        storage
+            .seed_local("prefs", &serde_json::json!({ "theme": "dark", "size": 2 }))
+            .seed_session("draft", &"hello")
+            .seed_cookie("session", "abc; path=/");
+        storage
+            .assert_local_storage("prefs", &serde_json::json!({ "size": 2, "theme": "dark" }))
+            .assert_session_storage("draft", &"hello")
+            .assert_cookie("session", Some("abc"));
+
+        drop(storage);
+
+        assert_eq!(
+            Area::Local.storage().get_item("before").unwrap().as_deref(),
+            Some("1")
+        );
+        assert_eq!(Area::Local.storage().get_item("prefs").unwrap(), None);
+        // restored with the path it had, instead of next to it
+        assert_eq!(
+            cookies()
+                .iter()
+                .filter(|(name, _)| name == "before")
+                .collect::<Vec<_>>(),
+            vec![&("before".to_string(), "1".to_string())]
+        );
+        assert!(!cookies().iter().any(|(name, _)| name == "session"));
+
+        Area::Local.storage().remove_item("before").unwrap();
+        clear_cookies();
+    }
+
+    #[wasm_bindgen_test]
+    #[should_panic = "unexpected value under `theme` in localStorage"]
+    fn fails_on_other_values() {
+        let storage = StorageSandbox::install();
+        storage.seed_local("theme", &"dark");
+        let stored = Area::Local.storage().get_item("theme").unwrap();
+        // tests run with `panic=abort`, so the sandbox has to be restored before panicking
+        drop(storage);
+
+        assert_stored_item(Area::Local, "theme", stored, &"light");
+    }
+}
@@ -0,0 +1,63 @@
+//! Helpers for waiting on the browser's event loop
+//!
+//! They always use the real timers, even when [`FakeTimers`](crate::timers::FakeTimers) are
+//! installed
+
+use wasm_bindgen::JsValue;
+
+use crate::{mutations, timers};
+
+/// How many macrotasks [`until_quiescent`] waits for before giving up
+const MAX_QUIESCENT_ROUNDS: usize = 100;
+
+/// Waits for the next microtask
+pub(crate) async fn next_microtask() {
+    let promise = js_sys::Promise::resolve(&JsValue::UNDEFINED);
+    wasm_bindgen_futures::JsFuture::from(promise).await.unwrap();
+}
+
+/// Waits for the next macrotask, so every pending microtask has run by then
+pub(crate) async fn next_macrotask() {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        timers::real("setTimeout")
+            .call1(&JsValue::NULL, &resolve)
+            .unwrap();
+    });
+    wasm_bindgen_futures::JsFuture::from(promise).await.unwrap();
+}
+
+/// Waits for `duration` to pass, as measured by `setTimeout`
+pub(crate) async fn sleep(duration: std::time::Duration) {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        timers::real("setTimeout")
+            .call2(
+                &JsValue::NULL,
+                &resolve,
+                &(duration.as_millis() as f64).into(),
+            )
+            .unwrap();
+    });
+    wasm_bindgen_futures::JsFuture::from(promise).await.unwrap();
+}
+
+/// Waits for the next `requestAnimationFrame` callback
+pub(crate) async fn next_animation_frame() {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        timers::real("requestAnimationFrame")
+            .call1(&JsValue::NULL, &resolve)
+            .unwrap();
+    });
+    wasm_bindgen_futures::JsFuture::from(promise).await.unwrap();
+}
+
+/// Waits until a whole macrotask passes without any changes to the DOM under `root`
+pub(crate) async fn until_quiescent(root: &web_sys::Element) {
+    for _ in 0..MAX_QUIESCENT_ROUNDS {
+        let (mutations, _) = mutations::record(root, next_macrotask).await;
+        if mutations.is_empty() {
+            return;
+        }
+    }
+
+    panic!("the DOM didn't stop changing after {MAX_QUIESCENT_ROUNDS} macrotasks");
+}
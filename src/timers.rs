@@ -0,0 +1,708 @@
+//! Fake timers, for testing debounces, timeouts and polling without waiting for real
+//!
+//! While a [`FakeTimers`] is installed, `setTimeout`, `setInterval`, `requestAnimationFrame` (and
+//! their counterparts for cancelling), `Date` and `performance.now` run on a fake clock that only
+//! moves when the test advances it:
+//!
+//! ```ignore
+//! let timers = FakeTimers::install();
+//! let wrapper = mount_test(|| view! { <Toast message="Saved" /> });
+//!
+//! timers.advance_by(&wrapper, Duration::from_secs(5)).await;
+//! wrapper.query(".toast").assert_not_exists();
+//! ```
+//!
+//! The wrapper passed when advancing is settled (see [`TestWrapper::settle`]) after every timer
+//! that runs, so the view has caught up by the time an advance returns. The helpers this crate uses
+//! for waiting (e.g. on settling) keep using the real timers.
+
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use wasm_bindgen::{JsCast as _, JsValue, closure::Closure};
+
+use crate::{
+    framework::Framework,
+    wrapper::{TestWrapper, TestWrapperState},
+};
+
+/// How long an animation frame lasts on the fake clock, about 60 frames per second
+const FRAME_MS: f64 = 16.0;
+
+/// How many timers [`FakeTimers::run_all`] runs before assuming they'll never stop
+const MAX_RUN_ALL_TIMERS: usize = 1000;
+
+/// The globals that get faked, as `(object, property)`. The ones in [`DISPATCHED`] are called
+/// through [`Dispatch`] instead of being replaced
+const PATCHED: &[(&str, &str)] = &[
+    ("globalThis", "setTimeout"),
+    ("globalThis", "clearTimeout"),
+    ("globalThis", "setInterval"),
+    ("globalThis", "clearInterval"),
+    ("globalThis", "requestAnimationFrame"),
+    ("globalThis", "cancelAnimationFrame"),
+    ("globalThis", "Date"),
+    ("performance", "now"),
+];
+
+/// `setInterval` and `clearInterval`, which other parts of the library watch (see [`observe`])
+const DISPATCHED: [&str; 2] = ["setInterval", "clearInterval"];
+
+type Observer = Rc<dyn Fn(js_sys::Array, JsValue)>;
+
+thread_local! {
+    static CLOCK: RefCell<Option<Clock>> = const { RefCell::new(None) };
+    static DISPATCH: RefCell<Option<Dispatch>> = const { RefCell::new(None) };
+}
+
+/// The single patch of [`DISPATCHED`], which calls the fakes while they're installed and the real
+/// functions otherwise, then tells the observers. It stays in place for the rest of the page's
+/// life, so whatever is installed first, neither the fakes nor the observers get lost
+struct Dispatch {
+    /// The real functions, in the same order as [`DISPATCHED`]
+    originals: Vec<js_sys::Function>,
+    /// With the index in [`DISPATCHED`] of what they observe
+    observers: Vec<(usize, Observer)>,
+}
+
+struct Clock {
+    /// The fake `performance.now()`
+    now: f64,
+    /// What to add to [`Self::now`] to get the fake `Date.now()`
+    date_offset: f64,
+    next_id: u32,
+    next_seq: u64,
+    timers: Vec<Timer>,
+    /// The values of [`PATCHED`] before installing, in the same order
+    originals: Vec<JsValue>,
+    /// The replacements for [`PATCHED`], in the same order
+    fakes: Vec<JsValue>,
+}
+
+struct Timer {
+    id: u32,
+    at: f64,
+    /// Breaks ties between timers due at the same time, in the order they were scheduled
+    seq: u64,
+    callback: js_sys::Function,
+    args: js_sys::Array,
+    kind: TimerKind,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum TimerKind {
+    Timeout,
+    Interval(f64),
+    AnimationFrame,
+}
+
+impl Clock {
+    fn schedule(
+        &mut self,
+        callback: js_sys::Function,
+        at: f64,
+        args: js_sys::Array,
+        kind: TimerKind,
+    ) -> u32 {
+        self.next_id += 1;
+        self.next_seq += 1;
+        self.timers.push(Timer {
+            id: self.next_id,
+            at,
+            seq: self.next_seq,
+            callback,
+            args,
+            kind,
+        });
+        self.next_id
+    }
+
+    fn cancel(&mut self, id: u32) {
+        self.timers.retain(|timer| timer.id != id);
+    }
+
+    /// Takes the next timer due at or before `until`, rescheduling it if it's an interval
+    fn take_next(
+        &mut self,
+        until: f64,
+        only: Option<&[u32]>,
+    ) -> Option<(js_sys::Function, js_sys::Array)> {
+        let idx = self
+            .timers
+            .iter()
+            .enumerate()
+            .filter(|(_, timer)| timer.at <= until)
+            .filter(|(_, timer)| only.is_none_or(|ids| ids.contains(&timer.id)))
+            .min_by(|(_, a), (_, b)| a.at.total_cmp(&b.at).then(a.seq.cmp(&b.seq)))
+            .map(|(idx, _)| idx)?;
+
+        let at = self.timers[idx].at;
+        self.now = self.now.max(at);
+
+        let timer = &mut self.timers[idx];
+        let callback = timer.callback.clone();
+        let args = match timer.kind {
+            TimerKind::AnimationFrame => js_sys::Array::of1(&self.now.into()),
+            _ => timer.args.clone(),
+        };
+        match timer.kind {
+            TimerKind::Interval(interval) => {
+                timer.at += interval.max(1.0);
+                self.next_seq += 1;
+                timer.seq = self.next_seq;
+            }
+            _ => {
+                self.timers.remove(idx);
+            }
+        }
+
+        Some((callback, args))
+    }
+}
+
+fn with_clock<T>(f: impl FnOnce(&mut Clock) -> T) -> T {
+    CLOCK.with_borrow_mut(|clock| f(clock.as_mut().expect("fake timers aren't installed")))
+}
+
+fn global(object: &str) -> JsValue {
+    let global = js_sys::global();
+    match object {
+        "globalThis" => global.into(),
+        object => js_sys::Reflect::get(&global, &object.into()).unwrap(),
+    }
+}
+
+fn patched_idx(name: &str) -> Option<usize> {
+    PATCHED
+        .iter()
+        .position(|&(object, property)| object == "globalThis" && property == name)
+}
+
+/// The real version of a global function that may be faked, e.g. `"setTimeout"`
+pub(crate) fn real(name: &str) -> js_sys::Function {
+    if let Some(idx) = DISPATCHED.iter().position(|&dispatched| dispatched == name) {
+        let original =
+            DISPATCH.with_borrow(|dispatch| Some(dispatch.as_ref()?.originals[idx].clone()));
+        if let Some(original) = original {
+            return original;
+        }
+    }
+
+    let original =
+        CLOCK.with_borrow(|clock| Some(clock.as_ref()?.originals[patched_idx(name)?].clone()));
+
+    original
+        .unwrap_or_else(|| js_sys::Reflect::get(&js_sys::global(), &name.into()).unwrap())
+        .unchecked_into()
+}
+
+/// The real `Date.now()`, for deadlines that shouldn't depend on the fake clock
+#[cfg(any(feature = "leptos", test))]
+pub(crate) fn real_now() -> f64 {
+    let date = real("Date");
+    let now: js_sys::Function = js_sys::Reflect::get(&date, &"now".into())
+        .unwrap()
+        .unchecked_into();
+    now.call0(&date).unwrap().as_f64().unwrap()
+}
+
+/// Calls `record` with the arguments and the result of every call to `name` (one of
+/// [`DISPATCHED`]), faked or not, for the rest of the page's life
+#[cfg(feature = "leptos")]
+pub(crate) fn observe(name: &str, record: impl Fn(js_sys::Array, JsValue) + 'static) {
+    let idx = DISPATCHED
+        .iter()
+        .position(|&dispatched| dispatched == name)
+        .unwrap_or_else(|| panic!("`{name}` can't be observed"));
+
+    patch_dispatched();
+    DISPATCH.with_borrow_mut(|dispatch| {
+        dispatch
+            .as_mut()
+            .unwrap()
+            .observers
+            .push((idx, Rc::new(record)));
+    });
+}
+
+/// Puts [`Dispatch`] in place if it isn't yet
+fn patch_dispatched() {
+    if DISPATCH.with_borrow(Option::is_some) {
+        return;
+    }
+
+    let global = js_sys::global();
+    let forward = js_sys::Function::new_with_args(
+        "dispatch",
+        "return function (...args) {
+            return dispatch(args);
+        };",
+    );
+    let originals = DISPATCHED
+        .iter()
+        .enumerate()
+        .map(|(idx, &name)| {
+            let original = js_sys::Reflect::get(&global, &name.into()).unwrap();
+            let dispatch =
+                Closure::<dyn Fn(js_sys::Array) -> Result<JsValue, JsValue>>::new(move |args| {
+                    call_dispatched(idx, args)
+                });
+            let dispatcher = forward
+                .call1(&JsValue::NULL, &dispatch.into_js_value())
+                .unwrap();
+            js_sys::Reflect::set(&global, &name.into(), &dispatcher).unwrap();
+
+            original.unchecked_into()
+        })
+        .collect();
+
+    DISPATCH.set(Some(Dispatch {
+        originals,
+        observers: Vec::new(),
+    }));
+}
+
+fn call_dispatched(idx: usize, args: js_sys::Array) -> Result<JsValue, JsValue> {
+    let fake = CLOCK.with_borrow(|clock| {
+        let clock = clock.as_ref()?;
+        clock.fakes.get(patched_idx(DISPATCHED[idx])?).cloned()
+    });
+    let (original, observers) = DISPATCH.with_borrow(|dispatch| {
+        let dispatch = dispatch.as_ref().unwrap();
+        let observers = dispatch
+            .observers
+            .iter()
+            .filter(|(observed, _)| *observed == idx)
+            .map(|(_, observer)| Rc::clone(observer))
+            .collect::<Vec<_>>();
+        (dispatch.originals[idx].clone(), observers)
+    });
+
+    let function = fake.map_or(original, |fake| fake.unchecked_into());
+    let result = function.apply(&JsValue::NULL, &args)?;
+    for observer in observers {
+        observer(args.clone(), result.clone());
+    }
+
+    Ok(result)
+}
+
+/// The fake clock, installed until this is dropped. See the [module docs](self)
+pub struct FakeTimers {
+    _closures: Vec<JsValue>,
+}
+
+impl FakeTimers {
+    /// Replaces the timer globals with fakes, starting the fake clock at the current time
+    ///
+    /// Panics if fake timers are already installed
+    pub fn install() -> Self {
+        assert!(
+            CLOCK.with_borrow(Option::is_none),
+            "fake timers are already installed"
+        );
+        patch_dispatched();
+
+        let real_now = js_sys::Date::now();
+        let performance_now = web_sys::window().unwrap().performance().unwrap().now();
+        let originals = PATCHED
+            .iter()
+            .map(|&(object, property)| {
+                js_sys::Reflect::get(&global(object), &property.into()).unwrap()
+            })
+            .collect::<Vec<_>>();
+        let original_date = originals[6].clone();
+
+        CLOCK.set(Some(Clock {
+            now: performance_now,
+            date_offset: real_now - performance_now,
+            next_id: 0,
+            next_seq: 0,
+            timers: Vec::new(),
+            originals,
+            fakes: Vec::new(),
+        }));
+
+        let set_timeout = Closure::<dyn Fn(JsValue, f64, js_sys::Array) -> u32>::new(
+            |callback: JsValue, delay: f64, args| {
+                with_clock(|clock| {
+                    let at = clock.now + delay.max(0.0);
+                    clock.schedule(as_function(callback), at, args, TimerKind::Timeout)
+                })
+            },
+        );
+        let set_interval = Closure::<dyn Fn(JsValue, f64, js_sys::Array) -> u32>::new(
+            |callback: JsValue, delay: f64, args| {
+                with_clock(|clock| {
+                    let interval = delay.max(0.0);
+                    let at = clock.now + interval.max(1.0);
+                    clock.schedule(
+                        as_function(callback),
+                        at,
+                        args,
+                        TimerKind::Interval(interval),
+                    )
+                })
+            },
+        );
+        let request_animation_frame = Closure::<dyn Fn(js_sys::Function) -> u32>::new(|callback| {
+            with_clock(|clock| {
+                let at = next_frame(clock.now);
+                clock.schedule(
+                    callback,
+                    at,
+                    js_sys::Array::new(),
+                    TimerKind::AnimationFrame,
+                )
+            })
+        });
+        let cancel = Closure::<dyn Fn(JsValue)>::new(|id: JsValue| {
+            if let Some(id) = id.as_f64() {
+                with_clock(|clock| clock.cancel(id as u32));
+            }
+        });
+        let performance_now = Closure::<dyn Fn() -> f64>::new(|| with_clock(|clock| clock.now));
+        let date_now =
+            Closure::<dyn Fn() -> f64>::new(|| with_clock(|clock| clock.now + clock.date_offset));
+
+        let variadic = js_sys::Function::new_with_args(
+            "schedule",
+            "return function (callback, delay, ...args) {
+                return schedule(callback, Number(delay) || 0, args);
+            };",
+        );
+        let fake_date = js_sys::Function::new_with_args(
+            "OriginalDate, now",
+            "class FakeDate extends OriginalDate {
+                constructor(...args) {
+                    if (args.length === 0) {
+                        super(now());
+                    } else {
+                        super(...args);
+                    }
+                }
+
+                static now() {
+                    return now();
+                }
+            }
+
+            // a class can't be called without `new`, but `Date()` returns the date as a string
+            function Date(...args) {
+                if (new.target === undefined) {
+                    return new FakeDate().toString();
+                }
+                return Reflect.construct(FakeDate, args, new.target);
+            }
+            Date.prototype = FakeDate.prototype;
+            Date.prototype.constructor = Date;
+            Object.setPrototypeOf(Date, FakeDate);
+            return Date;",
+        );
+
+        let fakes = [
+            variadic
+                .call1(&JsValue::NULL, set_timeout.as_ref())
+                .unwrap(),
+            cancel.as_ref().clone(),
+            variadic
+                .call1(&JsValue::NULL, set_interval.as_ref())
+                .unwrap(),
+            cancel.as_ref().clone(),
+            request_animation_frame.as_ref().clone(),
+            cancel.as_ref().clone(),
+            fake_date
+                .call2(&JsValue::NULL, &original_date, date_now.as_ref())
+                .unwrap(),
+            performance_now.as_ref().clone(),
+        ];
+        for (&(object, property), fake) in PATCHED.iter().zip(&fakes) {
+            if !DISPATCHED.contains(&property) {
+                js_sys::Reflect::set(&global(object), &property.into(), fake).unwrap();
+            }
+        }
+        with_clock(|clock| clock.fakes = fakes.to_vec());
+
+        Self {
+            // the closures are only dropped once the globals are restored
+            _closures: vec![
+                set_timeout.into_js_value(),
+                set_interval.into_js_value(),
+                request_animation_frame.into_js_value(),
+                cancel.into_js_value(),
+                performance_now.into_js_value(),
+                date_now.into_js_value(),
+            ],
+        }
+    }
+
+    /// The fake `performance.now()`
+    pub fn now(&self) -> Duration {
+        Duration::from_secs_f64(with_clock(|clock| clock.now) / 1000.0)
+    }
+
+    /// How many timers and animation frames are scheduled
+    pub fn pending_count(&self) -> usize {
+        with_clock(|clock| clock.timers.len())
+    }
+
+    /// Moves the clock forward by `duration`, running every timer that's due on the way (including
+    /// the ones scheduled by those timers) and settling `wrapper` after each
+    pub async fn advance_by<State, Fw>(
+        &self,
+        wrapper: &TestWrapper<State, Fw>,
+        duration: Duration,
+    ) -> &Self
+    where
+        State: TestWrapperState,
+        Fw: Framework,
+    {
+        let until = with_clock(|clock| clock.now) + duration.as_secs_f64() * 1000.0;
+        run_until(wrapper, until, None).await;
+        with_clock(|clock| clock.now = clock.now.max(until));
+
+        self
+    }
+
+    /// Moves the clock to the next animation frame and runs its callbacks, along with any timer
+    /// that's due before it, then settles `wrapper`
+    pub async fn next_animation_frame<State, Fw>(&self, wrapper: &TestWrapper<State, Fw>) -> &Self
+    where
+        State: TestWrapperState,
+        Fw: Framework,
+    {
+        let frame = with_clock(|clock| next_frame(clock.now));
+        run_until(wrapper, frame, None).await;
+        with_clock(|clock| clock.now = clock.now.max(frame));
+
+        self
+    }
+
+    /// Runs every timer until there are none left, moving the clock along and settling `wrapper`
+    /// after each
+    ///
+    /// Panics if timers keep getting scheduled, as with a `setInterval` that's never cleared
+    pub async fn run_all<State, Fw>(&self, wrapper: &TestWrapper<State, Fw>) -> &Self
+    where
+        State: TestWrapperState,
+        Fw: Framework,
+    {
+        for _ in 0..MAX_RUN_ALL_TIMERS {
+            if !run_next(wrapper, f64::INFINITY, None).await {
+                return self;
+            }
+        }
+
+        panic!(
+            "ran {MAX_RUN_ALL_TIMERS} timers and there are still more, is there an interval that's never cleared?"
+        );
+    }
+
+    /// Runs only the timers that are scheduled right now, moving the clock along and settling
+    /// `wrapper` after each. The ones they schedule are left pending, and intervals run once
+    pub async fn run_only_pending<State, Fw>(&self, wrapper: &TestWrapper<State, Fw>) -> &Self
+    where
+        State: TestWrapperState,
+        Fw: Framework,
+    {
+        let pending = with_clock(|clock| {
+            clock
+                .timers
+                .iter()
+                .map(|timer| timer.id)
+                .collect::<Vec<_>>()
+        });
+        let mut ran = Vec::new();
+
+        loop {
+            let remaining = pending
+                .iter()
+                .copied()
+                .filter(|id| !ran.contains(id))
+                .collect::<Vec<_>>();
+            let next = with_clock(|clock| {
+                clock
+                    .timers
+                    .iter()
+                    .filter(|timer| remaining.contains(&timer.id))
+                    .min_by(|a, b| a.at.total_cmp(&b.at).then(a.seq.cmp(&b.seq)))
+                    .map(|timer| timer.id)
+            });
+            let Some(id) = next else {
+                return self;
+            };
+
+            ran.push(id);
+            run_next(wrapper, f64::INFINITY, Some(&[id])).await;
+        }
+    }
+}
+
+async fn run_until<State, Fw>(wrapper: &TestWrapper<State, Fw>, until: f64, only: Option<&[u32]>)
+where
+    State: TestWrapperState,
+    Fw: Framework,
+{
+    while run_next(wrapper, until, only).await {}
+}
+
+/// Runs the next timer due at or before `until` and settles `wrapper`, returns whether there was
+/// any
+async fn run_next<State, Fw>(
+    wrapper: &TestWrapper<State, Fw>,
+    until: f64,
+    only: Option<&[u32]>,
+) -> bool
+where
+    State: TestWrapperState,
+    Fw: Framework,
+{
+    let Some((callback, args)) = with_clock(|clock| clock.take_next(until, only)) else {
+        return false;
+    };
+
+    if let Err(err) = callback.apply(&JsValue::NULL, &args) {
+        panic!("a timer's callback threw: {err:?}");
+    }
+    wrapper.settle().await;
+
+    true
+}
+
+impl Drop for FakeTimers {
+    fn drop(&mut self) {
+        let clock = CLOCK.take().unwrap();
+        for (&(object, property), original) in PATCHED.iter().zip(&clock.originals) {
+            if !DISPATCHED.contains(&property) {
+                js_sys::Reflect::set(&global(object), &property.into(), original).unwrap();
+            }
+        }
+    }
+}
+
+/// Timers can also take a string of code to run, which is turned into a function
+fn as_function(callback: JsValue) -> js_sys::Function {
+    match callback.dyn_into::<js_sys::Function>() {
+        Ok(callback) => callback,
+        Err(code) => js_sys::Function::new_no_args(&code.as_string().unwrap_or_default()),
+    }
+}
+
+/// When the frame after `now` starts
+fn next_frame(now: f64) -> f64 {
+    ((now / FRAME_MS).floor() + 1.0) * FRAME_MS
+}
+
+#[cfg(all(test, target_family = "wasm"))]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+    use crate::{BaseTestWrapper, Vanilla, framework::SettleStrategy};
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn counter() -> (Rc<Cell<u32>>, js_sys::Function) {
+        let count = Rc::new(Cell::new(0));
+        let count_clone = Rc::clone(&count);
+        let callback = Closure::<dyn Fn()>::new(move || count_clone.set(count_clone.get() + 1));
+        (count, callback.into_js_value().unchecked_into())
+    }
+
+    #[wasm_bindgen_test]
+    async fn advances_timeouts_and_intervals() {
+        let timers = FakeTimers::install();
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html("");
+        let window = web_sys::window().unwrap();
+        let (timeouts, timeout) = counter();
+        let (intervals, interval) = counter();
+
+        window
+            .set_timeout_with_callback_and_timeout_and_arguments_0(&timeout, 1000)
+            .unwrap();
+        let interval_id = window
+            .set_interval_with_callback_and_timeout_and_arguments_0(&interval, 300)
+            .unwrap();
+
+        timers
+            .advance_by(&wrapper, Duration::from_millis(999))
+            .await;
+        assert_eq!((timeouts.get(), intervals.get()), (0, 3));
+
+        timers.advance_by(&wrapper, Duration::from_millis(1)).await;
+        assert_eq!((timeouts.get(), intervals.get()), (1, 3));
+
+        window.clear_interval_with_handle(interval_id);
+        timers.run_all(&wrapper).await;
+        assert_eq!(intervals.get(), 3);
+        assert_eq!(timers.pending_count(), 0);
+    }
+
+    #[wasm_bindgen_test]
+    async fn fakes_the_date() {
+        let timers = FakeTimers::install();
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html("");
+        let before = js_sys::Date::now();
+
+        timers.advance_by(&wrapper, Duration::from_secs(60)).await;
+
+        assert_eq!(js_sys::Date::now() - before, 60_000.0);
+        assert_eq!(js_sys::Date::new_0().get_time(), js_sys::Date::now());
+        assert_eq!(
+            js_sys::Reflect::get(&js_sys::global(), &"Date".into())
+                .unwrap()
+                .unchecked_into::<js_sys::Function>()
+                .call0(&JsValue::NULL)
+                .unwrap(),
+            JsValue::from(js_sys::Date::new_0().to_string())
+        );
+        assert!(real_now() - before < 60_000.0);
+    }
+
+    #[wasm_bindgen_test]
+    async fn runs_only_pending_timers() {
+        let timers = FakeTimers::install();
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html("");
+        let window = web_sys::window().unwrap();
+        let (frames, frame) = counter();
+        let (intervals, interval) = counter();
+
+        window.request_animation_frame(&frame).unwrap();
+        window
+            .set_interval_with_callback_and_timeout_and_arguments_0(&interval, 10_000)
+            .unwrap();
+
+        timers.next_animation_frame(&wrapper).await;
+        assert_eq!((frames.get(), intervals.get()), (1, 0));
+
+        timers.run_only_pending(&wrapper).await;
+        assert_eq!(intervals.get(), 1);
+        assert_eq!(timers.pending_count(), 1);
+    }
+
+    #[wasm_bindgen_test]
+    async fn settles_the_wrapper() {
+        let timers = FakeTimers::install();
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html("<p>saving</p>")
+            .with_settle_strategy(SettleStrategy::Tick);
+
+        // the text only changes in a microtask queued by the timer
+        let callback = js_sys::Function::new_with_args(
+            "p",
+            "Promise.resolve().then(() => { p.textContent = 'saved'; });",
+        );
+        web_sys::window()
+            .unwrap()
+            .set_timeout_with_callback_and_timeout_and_arguments_1(
+                &callback,
+                500,
+                &wrapper.query("p").assert_exists(),
+            )
+            .unwrap();
+
+        timers
+            .advance_by(&wrapper, Duration::from_millis(500))
+            .await;
+        wrapper.query("p").assert_exists().assert_text_is("saved");
+    }
+}
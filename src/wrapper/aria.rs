@@ -0,0 +1,121 @@
+use crate::{
+    aria::{self, AriaNode},
+    framework::Framework,
+    snapshot::line_diff,
+};
+
+use super::{TestWrapper, empty::Empty, single::Single};
+
+impl<Fw: Framework> TestWrapper<Empty, Fw> {
+    /// Renders the accessibility tree of everything mounted in the root
+    pub fn aria_snapshot(&self) -> String {
+        aria::render(&aria::aria_tree(&self.root))
+    }
+
+    /// Asserts that the accessibility tree of the root matches the expected snapshot.
+    ///
+    /// Matching is partial: any name, state or node left out of `expected` is ignored, and it can match
+    /// any subtree
+    pub fn assert_aria_snapshot(&self, expected: &str) -> &Self {
+        assert_aria_matches(&aria::aria_tree(&self.root), expected);
+        self
+    }
+}
+
+impl<E: Into<web_sys::Element> + Clone, Fw: Framework> TestWrapper<Single<E>, Fw> {
+    /// Renders the accessibility tree of this element and its subtree
+    pub fn aria_snapshot(&self) -> String {
+        aria::render(&aria::aria_tree_of_element(&self.state.0.clone().into()))
+    }
+
+    /// Asserts that the accessibility tree of this element matches the expected snapshot.
+    ///
+    /// Matching is partial: any name, state or node left out of `expected` is ignored, and it can match
+    /// any subtree
+    pub fn assert_aria_snapshot(&self, expected: &str) -> &Self {
+        assert_aria_matches(
+            &aria::aria_tree_of_element(&self.state.0.clone().into()),
+            expected,
+        );
+        self
+    }
+}
+
+fn assert_aria_matches(tree: &[AriaNode], expected: &str) {
+    let templates = aria::template::parse(expected).unwrap_or_else(|err| panic!("{err}"));
+    if !aria::template::matches_anywhere(&templates, tree) {
+        let actual = aria::render(tree);
+        panic!(
+            "aria snapshot does not match:\n{}\nactual:\n{actual}",
+            line_diff(expected.trim(), &actual)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{BaseTestWrapper, Vanilla};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test(unsupported = test)]
+    #[cfg_attr(not(target_family = "wasm"), ignore)]
+    fn renders_aria_tree() {
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html(concat!(
+            "<main>",
+            "<h1>Todos</h1>",
+            r#"<div><ul><li><label><input type="checkbox" checked />Buy milk</label></li></ul></div>"#,
+            r#"<label for="new">New todo</label>"#,
+            r#"<input id="new" type="text" value="Bread" />"#,
+            r#"<button aria-expanded="true">Add</button>"#,
+            "</main>",
+        ));
+
+        assert_eq!(
+            wrapper.aria_snapshot(),
+            [
+                "- main:",
+                "  - heading \"Todos\" [level=1]",
+                "  - list:",
+                "    - listitem:",
+                "      - checkbox \"Buy milk\" [checked]",
+                "      - text: Buy milk",
+                "  - text: New todo",
+                "  - textbox \"New todo\": Bread",
+                "  - button \"Add\" [expanded]",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    #[cfg_attr(not(target_family = "wasm"), ignore)]
+    fn matches_partial_aria_snapshot() {
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html(
+            r#"<nav aria-label="Main"><a href="/">Home</a><a href="/about">About</a></nav>"#,
+        );
+
+        wrapper.assert_aria_snapshot(
+            r#"
+            - navigation "Main":
+              - link "About"
+            "#,
+        );
+
+        wrapper
+            .query("nav")
+            .assert_exists()
+            .assert_aria_snapshot("- link: Home");
+    }
+
+    #[should_panic(expected = "aria snapshot does not match")]
+    #[wasm_bindgen_test(unsupported = test)]
+    #[cfg_attr(not(target_family = "wasm"), ignore)]
+    fn aria_snapshot_mismatch_panics() {
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html("<button>Save</button>");
+
+        wrapper.assert_aria_snapshot("- button \"Cancel\"");
+    }
+}
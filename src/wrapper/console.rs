@@ -0,0 +1,123 @@
+use crate::{
+    console::{ConsoleCapture, ConsoleLevel, ConsoleMessage},
+    framework::Framework,
+};
+
+use super::{TestWrapper, TestWrapperState};
+
+impl<T: TestWrapperState, Fw: Framework> TestWrapper<T, Fw> {
+    /// Captures the console (see [`ConsoleCapture`]) until every wrapper of this mount is dropped
+    ///
+    /// Only what's printed from now on is captured. To also see what's printed while mounting,
+    /// install a [`ConsoleCapture`] before mounting instead
+    ///
+    /// ```ignore
+    /// let wrapper = mount_test(|| view! { <Counter /> }).with_console_capture();
+    /// wrapper.query_as_button("button").assert_exists().click().await;
+    /// wrapper.assert_no_console_errors();
+    /// ```
+    pub fn with_console_capture(self) -> Self {
+        self.console.get_or_init(ConsoleCapture::install);
+        self
+    }
+
+    /// Like [`Self::with_console_capture`], but the test fails once every wrapper of this mount is
+    /// dropped if anything was printed as a warning or an error, like Leptos warning about a signal
+    /// read outside of a tracking context
+    pub fn with_console_capture_strict(self) -> Self {
+        self.with_console_capture_failing_on(ConsoleLevel::Warn)
+    }
+
+    /// Like [`Self::with_console_capture`], but the test fails once every wrapper of this mount is
+    /// dropped if anything was printed at `level` or above
+    pub fn with_console_capture_failing_on(self, level: ConsoleLevel) -> Self {
+        self.console
+            .get_or_init(ConsoleCapture::install)
+            .fail_on
+            .set(Some(level));
+        self
+    }
+
+    /// Everything captured since [`Self::with_console_capture`], in order
+    pub fn captured_console(&self) -> Vec<ConsoleMessage> {
+        self.console_capture().captured_console()
+    }
+
+    /// Asserts that nothing was printed as an error since [`Self::with_console_capture`], nor was
+    /// there any uncaught error
+    pub fn assert_no_console_errors(&self) -> &Self {
+        self.console_capture().assert_no_console_errors();
+        self
+    }
+
+    /// Asserts that nothing was printed as a warning or an error since
+    /// [`Self::with_console_capture`]
+    pub fn assert_no_console_warnings(&self) -> &Self {
+        self.console_capture().assert_no_console_warnings();
+        self
+    }
+
+    fn console_capture(&self) -> &ConsoleCapture {
+        self.console
+            .get()
+            .expect("the console isn't captured, call `with_console_capture` first")
+    }
+}
+
+#[cfg(all(test, target_family = "wasm"))]
+mod tests {
+    use crate::{
+        BaseTestWrapper, Vanilla,
+        console::{ConsoleLevel, ConsoleMessage},
+    };
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn captures_the_console_of_the_mount() {
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html("<p>hi</p>").with_console_capture();
+        let p = wrapper.query("p").assert_exists();
+
+        web_sys::console::error_1(&"broken".into());
+        drop(wrapper);
+
+        assert_eq!(
+            p.captured_console(),
+            vec![ConsoleMessage {
+                level: ConsoleLevel::Error,
+                text: "broken".to_string(),
+            }]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    #[should_panic = "the console got 1 message(s) at Error level or above"]
+    fn fails_on_console_errors() {
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html("").with_console_capture();
+
+        web_sys::console::error_1(&"broken".into());
+        wrapper.assert_no_console_errors();
+    }
+
+    #[wasm_bindgen_test]
+    #[should_panic = "the console got 1 message(s) at Warn level or above"]
+    fn fails_on_console_warnings() {
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html("").with_console_capture();
+
+        web_sys::console::warn_1(&"careful".into());
+        wrapper.assert_no_console_warnings();
+    }
+
+    #[wasm_bindgen_test]
+    #[should_panic = "the console got 1 message(s) at Warn level or above"]
+    fn fails_when_the_mount_is_dropped() {
+        let wrapper =
+            BaseTestWrapper::<Vanilla>::from_html("<p>hi</p>").with_console_capture_strict();
+        let p = wrapper.query("p").assert_exists();
+
+        web_sys::console::warn_1(&"a signal was read outside of a tracking context".into());
+        drop(wrapper);
+        drop(p);
+    }
+}
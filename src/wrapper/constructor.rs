@@ -1,11 +1,17 @@
+use std::rc::Rc;
+
 use super::empty::Empty;
-use crate::{framework::Framework, wrapper::TestWrapper};
+use crate::{
+    framework::{Framework, vanilla::Vanilla},
+    isolation::TestRoot,
+    wrapper::TestWrapper,
+};
 
 cfg_if::cfg_if! {
     if #[cfg(feature = "leptos")] {
         pub type BaseTestWrapper<Fw = crate::framework::leptos::Leptos> = TestWrapper<Empty, Fw>;
     } else {
-        pub type BaseTestWrapper<Fw> = TestWrapper<Empty, Fw>;
+        pub type BaseTestWrapper<Fw = Vanilla> = TestWrapper<Empty, Fw>;
     }
 }
 
@@ -13,8 +19,78 @@ impl<Fw: Framework> BaseTestWrapper<Fw> {
     pub fn with_root(root: web_sys::Element, ctx: Fw::Context) -> Self {
         Self {
             root,
-            state: Empty,
+            state: Empty::new(),
             _framework_ctx: ctx,
+            settle_strategy: Default::default(),
+            console: Default::default(),
         }
     }
 }
+
+impl BaseTestWrapper<Vanilla> {
+    /// Parses the given HTML into a new element on the dom and returns a [`BaseTestWrapper`] for working with it
+    ///
+    /// The element is removed from the dom once every wrapper using it is dropped
+    pub fn from_html(html: &str) -> Self {
+        let test_root = TestRoot::new();
+        test_root.element().set_inner_html(html);
+
+        Self::with_root(test_root.element().clone(), Some(Rc::new(test_root)))
+    }
+
+    /// Uses an existing element as the root of a [`BaseTestWrapper`]
+    pub fn from_element(elem: &web_sys::Element) -> Self {
+        Self::with_root(elem.clone(), None)
+    }
+
+    /// Uses the whole document as the root of a [`BaseTestWrapper`]
+    pub fn from_document() -> Self {
+        let document = web_sys::window().unwrap().document().unwrap();
+        Self::with_root(document.document_element().unwrap(), None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{BaseTestWrapper, Vanilla};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test(unsupported = test)]
+    #[cfg_attr(not(target_family = "wasm"), ignore)]
+    fn from_html() {
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html(
+            r#"<form><input id="name" value="test" /></form>"#,
+        );
+
+        wrapper
+            .query_as_input("#name")
+            .assert_exists()
+            .assert_value_is("test");
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    #[cfg_attr(not(target_family = "wasm"), ignore)]
+    fn from_element() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let elem = document.create_element("div").unwrap();
+        elem.set_inner_html("<span>created by hand</span>");
+
+        BaseTestWrapper::<Vanilla>::from_element(&elem)
+            .query("span")
+            .assert_exists()
+            .assert_text_is("created by hand");
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    #[cfg_attr(not(target_family = "wasm"), ignore)]
+    fn from_document() {
+        let _fixture = BaseTestWrapper::<Vanilla>::from_html(r#"<p id="from-document">hi</p>"#);
+
+        BaseTestWrapper::<Vanilla>::from_document()
+            .query("body #from-document")
+            .assert_exists();
+    }
+}
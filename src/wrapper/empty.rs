@@ -1,20 +1,94 @@
+use std::rc::Rc;
+
 use wasm_bindgen::JsCast as _;
 
-use crate::{framework::Framework, util::NodeListExt};
+use crate::{framework::Framework, isolation, util::NodeListExt};
 
 use super::{Maybe, TestWrapper, TestWrapperState, many::Many, single::Single};
 
 /// The initial state for a [`TestWrapper`]: no element has been selected yet
-pub struct Empty;
+pub struct Empty {
+    /// For wrappers created with [`TestWrapper::screen`], the root of the wrapper it was called on
+    pub(super) screen_of: Option<web_sys::Element>,
+    /// Whether queries skip what's mounted in the roots of other wrappers, see
+    /// [`TestWrapper::exclude_other_roots`]
+    pub(super) exclude_other_roots: bool,
+}
 impl TestWrapperState for Empty {}
 
+impl Empty {
+    pub(super) fn new() -> Self {
+        Self {
+            screen_of: None,
+            exclude_other_roots: false,
+        }
+    }
+}
+
+impl<T: TestWrapperState, Fw: Framework> TestWrapper<T, Fw> {
+    /// A wrapper for querying the whole `<body>` instead of just this wrapper's root, for anything
+    /// rendered outside of it like portals, modals or toasts
+    ///
+    /// ```ignore
+    /// wrapper.query_as_button("#open-dialog").assert_exists().click().await;
+    /// wrapper.screen().query("[role=dialog]").assert_exists();
+    /// ```
+    ///
+    /// It keeps the framework context and the settle strategy of this wrapper, so interactions with
+    /// what it finds settle as usual
+    pub fn screen(&self) -> TestWrapper<Empty, Fw> {
+        let document = web_sys::window().unwrap().document().unwrap();
+        TestWrapper {
+            root: document.body().unwrap().into(),
+            state: Empty {
+                screen_of: Some(self.root.clone()),
+                exclude_other_roots: false,
+            },
+            _framework_ctx: self._framework_ctx.clone(),
+            settle_strategy: Rc::clone(&self.settle_strategy),
+            console: Rc::clone(&self.console),
+        }
+    }
+}
+
 // MAYBE I should remove the `query_*_unchecked` methods because "unchecked" usually means it's not safe, which is not the case since they just do an assertion. The names are confusing, and letting the user make the assertion themselves is no big hassle anyway
 // MAYBE querying should also be in some other sorts of wrappers, not just empty
 impl<Fw: Framework> TestWrapper<Empty, Fw> {
+    /// Makes queries skip whatever is mounted in the roots of other wrappers that are still alive,
+    /// so a [`Self::screen`] only sees this test's view and what it rendered elsewhere
+    pub fn exclude_other_roots(mut self) -> Self {
+        self.state.exclude_other_roots = true;
+        self
+    }
+
+    /// Whether `elem` is inside the root of some other wrapper and should be skipped
+    fn is_excluded(&self, elem: &web_sys::Element) -> bool {
+        let own_root = self.state.screen_of.as_ref().unwrap_or(&self.root);
+        self.state.exclude_other_roots && isolation::in_other_test_root(elem, own_root)
+    }
+
+    fn select_first(&self, selector: &str) -> Option<web_sys::Element> {
+        if self.state.exclude_other_roots {
+            self.select_all(selector).into_iter().next()
+        } else {
+            self.root.query_selector(selector).unwrap()
+        }
+    }
+
+    fn select_all(&self, selector: &str) -> Vec<web_sys::Element> {
+        let mut elems = self
+            .root
+            .query_selector_all(selector)
+            .expect("couldn't select nodes")
+            .to_elem_vec::<web_sys::Element>();
+        elems.retain(|elem| !self.is_excluded(elem));
+        elems
+    }
+
     /// Tries to find an element by the given CSS selector
     pub fn query(&self, selector: &str) -> TestWrapper<Maybe<web_sys::Element>, Fw> {
         self.derive(|_| Maybe {
-            elem: self.root.query_selector(selector).unwrap(),
+            elem: self.select_first(selector),
             selector: selector.to_string(),
         })
     }
@@ -23,9 +97,7 @@ impl<Fw: Framework> TestWrapper<Empty, Fw> {
     pub fn query_as<T: wasm_bindgen::JsCast>(&self, selector: &str) -> TestWrapper<Maybe<T>, Fw> {
         self.derive(|_| Maybe {
             elem: self
-                .root
-                .query_selector(selector)
-                .unwrap()
+                .select_first(selector)
                 .map(|elem| elem.unchecked_into()),
             selector: selector.to_string(),
         })
@@ -47,11 +119,7 @@ impl<Fw: Framework> TestWrapper<Empty, Fw> {
     /// Finds all elements that match the given CSS selector.
     pub fn query_all(&self, selector: &str) -> TestWrapper<Many<web_sys::Element>, Fw> {
         self.derive(|_| Many {
-            elems: self
-                .root
-                .query_selector_all(selector)
-                .expect("couldn't select nodes")
-                .to_elem_vec(),
+            elems: self.select_all(selector),
         })
     }
 
@@ -62,10 +130,17 @@ impl<Fw: Framework> TestWrapper<Empty, Fw> {
     ) -> TestWrapper<Many<T>, Fw> {
         self.derive(|_| Many {
             elems: self
-                .root
-                .query_selector_all(selector)
-                .expect("couldn't select nodes")
-                .to_elem_vec(),
+                .select_all(selector)
+                .into_iter()
+                .map(|elem| {
+                    elem.dyn_into().unwrap_or_else(|_| {
+                        panic!(
+                            "some node was not an instance of {}",
+                            std::any::type_name::<T>()
+                        )
+                    })
+                })
+                .collect(),
         })
     }
 
@@ -74,13 +149,7 @@ impl<Fw: Framework> TestWrapper<Empty, Fw> {
     ///
     /// This function is recursive! Hopefully your DOM isn't infinitely deep :^)
     pub fn find_by_text_exact(&self, text: &str) -> TestWrapper<Maybe<web_sys::Element>, Fw> {
-        self.derive(|_| Maybe {
-            elem: recursive_find_by_text_exact(
-                self.root.clone().dyn_into::<web_sys::Node>().unwrap(),
-                text,
-            ),
-            selector: format!("<text={text}>"),
-        })
+        self.find_by_text_exact_as(text)
     }
 
     pub fn find_by_text_exact_as<Target: wasm_bindgen::JsCast>(
@@ -91,6 +160,7 @@ impl<Fw: Framework> TestWrapper<Empty, Fw> {
             elem: recursive_find_by_text_exact::<Target>(
                 self.root.clone().dyn_into::<web_sys::Node>().unwrap(),
                 text,
+                &|elem| self.is_excluded(elem),
             ),
             selector: format!("<text={text}>"),
         })
@@ -100,7 +170,12 @@ impl<Fw: Framework> TestWrapper<Empty, Fw> {
 fn recursive_find_by_text_exact<Target: wasm_bindgen::JsCast>(
     root: web_sys::Node,
     needle: &str,
+    is_excluded: &dyn Fn(&web_sys::Element) -> bool,
 ) -> Option<Target> {
+    if root.dyn_ref::<web_sys::Element>().is_some_and(is_excluded) {
+        return None;
+    }
+
     let children = root.child_nodes();
 
     if let Ok(root_as) = root.clone().dyn_into::<Target>() {
@@ -113,7 +188,7 @@ fn recursive_find_by_text_exact<Target: wasm_bindgen::JsCast>(
     }
 
     for child in children.into_iterator() {
-        if let Some(matching_el) = recursive_find_by_text_exact(child, needle) {
+        if let Some(matching_el) = recursive_find_by_text_exact(child, needle, is_excluded) {
             return Some(matching_el);
         }
     }
@@ -145,6 +220,7 @@ impl_query_as!(
     input => web_sys::HtmlInputElement,
     button => web_sys::HtmlButtonElement,
     label => web_sys::HtmlLabelElement,
+    form => web_sys::HtmlFormElement,
 );
 
 #[cfg(test)]
@@ -152,7 +228,7 @@ mod tests {
     use leptos::prelude::*;
     use wasm_bindgen_test::*;
 
-    use crate::framework::leptos::mount_test;
+    use crate::{BaseTestWrapper, Vanilla, framework::leptos::mount_test};
 
     wasm_bindgen_test_configure!(run_in_browser);
 
@@ -208,4 +284,38 @@ mod tests {
 
         assert_eq!(result.len(), 3);
     }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    #[cfg_attr(not(target_family = "wasm"), ignore)]
+    fn screen_queries_the_whole_body() {
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html("<button>open</button>");
+        let document = web_sys::window().unwrap().document().unwrap();
+        let toast = document.create_element("div").unwrap();
+        toast.set_id("toast");
+        toast.set_text_content(Some("saved!"));
+        document.body().unwrap().append_child(&toast).unwrap();
+
+        wrapper.query("#toast").assert_not_exists();
+        wrapper.screen().query("#toast").assert_exists();
+        wrapper
+            .screen()
+            .find_by_text_exact("saved!")
+            .assert_exists();
+
+        toast.remove();
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    #[cfg_attr(not(target_family = "wasm"), ignore)]
+    fn screen_excludes_other_roots() {
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html(r#"<p class="item">mine</p>"#);
+        let _other = BaseTestWrapper::<Vanilla>::from_html(r#"<p class="item">theirs</p>"#);
+
+        assert!(wrapper.screen().query_all(".item").len() >= 2);
+
+        let screen = wrapper.screen().exclude_other_roots();
+        assert_eq!(screen.query_all(".item").len(), 1);
+        screen.query(".item").assert_exists().assert_text_is("mine");
+        screen.find_by_text_exact("theirs").assert_not_exists();
+    }
 }
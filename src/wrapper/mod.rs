@@ -1,18 +1,35 @@
+mod aria;
+mod console;
 mod constructor;
 mod empty;
 mod many;
 mod maybe;
+mod mutations;
 mod single;
+mod snapshot;
 
-use crate::framework::Framework;
+use std::{
+    cell::{Cell, OnceCell},
+    rc::Rc,
+};
+
+use crate::{
+    console::ConsoleCapture,
+    framework::{Framework, SettleStrategy},
+};
 use maybe::Maybe;
 
 pub use constructor::BaseTestWrapper;
+pub(crate) use single::Single;
 
 pub struct TestWrapper<State: TestWrapperState, Fw: Framework> {
     root: web_sys::Element,
     state: State,
     _framework_ctx: Fw::Context,
+    /// Shared by every wrapper of the same mount, and the handles mounted alongside them
+    pub(crate) settle_strategy: Rc<Cell<SettleStrategy>>,
+    /// Shared by every wrapper of the same mount, so the capture ends along with the mount
+    console: Rc<OnceCell<ConsoleCapture>>,
 }
 
 pub trait TestWrapperState {}
@@ -24,6 +41,8 @@ impl<T: TestWrapperState, Fw: Framework> TestWrapper<T, Fw> {
             root: self.root.clone(),
             state: state_fn(&self.state),
             _framework_ctx: self._framework_ctx.clone(),
+            settle_strategy: Rc::clone(&self.settle_strategy),
+            console: Rc::clone(&self.console),
         }
     }
 
@@ -33,6 +52,35 @@ impl<T: TestWrapperState, Fw: Framework> TestWrapper<T, Fw> {
             root: self.root,
             state: state_fn(self.state),
             _framework_ctx: self._framework_ctx,
+            settle_strategy: self.settle_strategy,
+            console: self.console,
         }
     }
+
+    /// The element everything in this wrapper is mounted into
+    pub fn root(&self) -> &web_sys::Element {
+        &self.root
+    }
+
+    /// The context that keeps the mounted framework app working
+    pub fn framework_ctx(&self) -> &Fw::Context {
+        &self._framework_ctx
+    }
+
+    /// Changes how this wrapper waits for changes after interactions, along with every other wrapper
+    /// of the same mount and the handles mounted with it (like a `PropsHandle`)
+    pub fn with_settle_strategy(self, strategy: SettleStrategy) -> Self {
+        self.settle_strategy.set(strategy);
+        self
+    }
+
+    /// Waits until the framework has applied any pending changes, according to the [`SettleStrategy`]
+    ///
+    /// Interactions already do this, but it's useful after changing some state from the test itself
+    pub async fn settle(&self) {
+        self.settle_strategy
+            .get()
+            .settle::<Fw>(&self._framework_ctx, &self.root)
+            .await;
+    }
 }
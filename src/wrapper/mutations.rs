@@ -0,0 +1,94 @@
+use std::future::Future;
+
+use crate::{
+    framework::Framework,
+    mutations::{self, Mutations},
+};
+
+use super::{TestWrapper, TestWrapperState};
+
+impl<T: TestWrapperState, Fw: Framework> TestWrapper<T, Fw> {
+    /// Records every change to the DOM under the root while `f` runs
+    ///
+    /// ```ignore
+    /// let mutations = wrapper
+    ///     .record_mutations(|| async { button.click().await; })
+    ///     .await;
+    /// mutations.assert_only_changed("#counter");
+    /// ```
+    pub async fn record_mutations<F, Fut>(&self, f: F) -> Mutations
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        mutations::record(&self.root, f).await.0
+    }
+}
+
+#[cfg(all(test, target_family = "wasm", feature = "leptos"))]
+mod tests {
+    use crate::framework::leptos::mount_test;
+    use leptos::prelude::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    async fn records_mutations() {
+        let wrapper = mount_test(|| {
+            let (count, set_count) = signal(0);
+            view! {
+                <p id="static">Static</p>
+                <p id="count" class:odd=move || count.get() % 2 == 1>
+                    {count}
+                </p>
+                <button on:click=move |_| set_count.update(|count| *count += 1)>Increment</button>
+            }
+        });
+        let button = wrapper.query_as_button("button").assert_exists();
+
+        let mutations = wrapper
+            .record_mutations(|| async {
+                button.click().await;
+            })
+            .await;
+
+        assert!(!mutations.is_empty());
+        mutations.assert_only_changed("#count");
+    }
+
+    #[wasm_bindgen_test]
+    async fn records_no_mutations() {
+        let wrapper = mount_test(|| {
+            view! { <button>Does nothing</button> }
+        });
+        let button = wrapper.query_as_button("button").assert_exists();
+
+        wrapper
+            .record_mutations(|| async {
+                button.click().await;
+            })
+            .await
+            .assert_no_mutations();
+    }
+
+    #[wasm_bindgen_test]
+    #[should_panic(expected = "there were mutations outside of it")]
+    async fn assert_only_changed_panics() {
+        let wrapper = mount_test(|| {
+            let (count, set_count) = signal(0);
+            view! {
+                <p id="count">{count}</p>
+                <button on:click=move |_| set_count.update(|count| *count += 1)>Increment</button>
+            }
+        });
+        let button = wrapper.query_as_button("button").assert_exists();
+
+        wrapper
+            .record_mutations(|| async {
+                button.click().await;
+            })
+            .await
+            .assert_only_changed("button");
+    }
+}
@@ -24,18 +24,79 @@ impl<E: Into<web_sys::Element> + Clone, Fw: Framework> TestWrapper<Single<E>, Fw
         self
     }
 
-    pub fn assert_class_contains(&self, expected: &str) -> &Self {
-        let state_elem: web_sys::Element = self.state.0.clone().into();
-        let classes = state_elem.get_attribute("class").unwrap_or_default();
-        assert!(classes.contains(expected));
+    /// Asserts that the element has the given class in its `classList`
+    ///
+    /// Classes are compared as whole tokens, so `active` won't match `inactive`
+    pub fn assert_has_class(&self, expected: &str) -> &Self {
+        let classes = self.class_list();
+        assert!(
+            classes.iter().any(|class| class == expected),
+            "element does not have class `{expected}`, current classes: {classes:?}"
+        );
+        self
+    }
+
+    /// Asserts that the element does not have the given class in its `classList`
+    pub fn assert_lacks_class(&self, unexpected: &str) -> &Self {
+        let classes = self.class_list();
+        assert!(
+            !classes.iter().any(|class| class == unexpected),
+            "element has class `{unexpected}`, current classes: {classes:?}"
+        );
         self
     }
 
+    /// Asserts that the element has all of the given classes, in any order.
+    ///
+    /// It may also have other classes. Use [`Self::assert_classes_exactly`] if it shouldn't
+    pub fn assert_has_classes(&self, expected: &[&str]) -> &Self {
+        let classes = self.class_list();
+        let missing = expected
+            .iter()
+            .filter(|expected| !classes.iter().any(|class| class == *expected))
+            .collect::<Vec<_>>();
+        assert!(
+            missing.is_empty(),
+            "element is missing classes {missing:?}, current classes: {classes:?}"
+        );
+        self
+    }
+
+    /// Asserts that the element's class set is exactly the given one, in any order.
+    pub fn assert_classes_exactly(&self, expected: &[&str]) -> &Self {
+        let classes = self.class_list();
+        let missing = expected
+            .iter()
+            .filter(|expected| !classes.iter().any(|class| class == *expected))
+            .collect::<Vec<_>>();
+        let unexpected = classes
+            .iter()
+            .filter(|class| !expected.contains(&class.as_str()))
+            .collect::<Vec<_>>();
+        assert!(
+            missing.is_empty() && unexpected.is_empty(),
+            "element classes differ: missing {missing:?}, unexpected {unexpected:?}, current classes: {classes:?}"
+        );
+        self
+    }
+
+    #[deprecated(note = "use `assert_has_class`")]
+    pub fn assert_class_contains(&self, expected: &str) -> &Self {
+        self.assert_has_class(expected)
+    }
+
+    #[deprecated(note = "use `assert_lacks_class`")]
     pub fn assert_class_not_contains(&self, expected: &str) -> &Self {
+        self.assert_lacks_class(expected)
+    }
+
+    /// The tokens in the element's `classList`, in order
+    fn class_list(&self) -> Vec<String> {
         let state_elem: web_sys::Element = self.state.0.clone().into();
-        let classes = state_elem.get_attribute("class").unwrap_or_default();
-        assert!(classes.contains(expected));
-        self
+        let class_list = state_elem.class_list();
+        (0..class_list.length())
+            .filter_map(|i| class_list.item(i))
+            .collect()
     }
 }
 
@@ -48,7 +109,6 @@ mod tests {
 
     wasm_bindgen_test_configure!(run_in_browser);
 
-    // TODO test class asserts
     #[wasm_bindgen_test(unsupported = test)]
     #[cfg_attr(not(target_family = "wasm"), ignore)]
     fn assert_text() {
@@ -66,4 +126,63 @@ mod tests {
             .assert_exists()
             .assert_text_contains("exists");
     }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    #[cfg_attr(not(target_family = "wasm"), ignore)]
+    fn assert_class() {
+        let wrapper = mount_test(|| {
+            view! { <span id="existent" class="active big">this exists</span> }
+        });
+
+        wrapper
+            .query("#existent")
+            .assert_exists()
+            .assert_has_class("active")
+            .assert_lacks_class("act")
+            .assert_lacks_class("inactive")
+            .assert_has_classes(&["big"])
+            .assert_classes_exactly(&["big", "active"]);
+    }
+
+    #[should_panic(expected = "element does not have class `active`")]
+    #[wasm_bindgen_test(unsupported = test)]
+    #[cfg_attr(not(target_family = "wasm"), ignore)]
+    fn assert_has_class_is_token_based() {
+        let wrapper = mount_test(|| {
+            view! { <span id="existent" class="inactive">this exists</span> }
+        });
+
+        wrapper
+            .query("#existent")
+            .assert_exists()
+            .assert_has_class("active");
+    }
+
+    #[should_panic(expected = "element has class `active`")]
+    #[wasm_bindgen_test(unsupported = test)]
+    #[cfg_attr(not(target_family = "wasm"), ignore)]
+    fn assert_lacks_class_panics() {
+        let wrapper = mount_test(|| {
+            view! { <span id="existent" class="active">this exists</span> }
+        });
+
+        wrapper
+            .query("#existent")
+            .assert_exists()
+            .assert_lacks_class("active");
+    }
+
+    #[should_panic(expected = "missing [\"small\"], unexpected [\"big\"]")]
+    #[wasm_bindgen_test(unsupported = test)]
+    #[cfg_attr(not(target_family = "wasm"), ignore)]
+    fn assert_classes_exactly_panics() {
+        let wrapper = mount_test(|| {
+            view! { <span id="existent" class="active big">this exists</span> }
+        });
+
+        wrapper
+            .query("#existent")
+            .assert_exists()
+            .assert_classes_exactly(&["active", "small"]);
+    }
 }
@@ -0,0 +1,94 @@
+use crate::{event_spy::EventSpy, framework::Framework, wrapper::TestWrapper};
+
+use super::Single;
+
+impl<T: AsRef<web_sys::Element>, Fw: Framework> TestWrapper<Single<T>, Fw> {
+    /// Records the events called `names` dispatched on this element or its descendants, until
+    /// the returned spy is dropped
+    ///
+    /// ```ignore
+    /// let spy = wrapper.query("my-dialog").assert_exists().spy_events(&["close"]);
+    /// ```
+    pub fn spy_events(&self, names: &[&str]) -> EventSpy {
+        EventSpy::new(self.state.0.as_ref(), names)
+    }
+}
+
+#[cfg(all(test, target_family = "wasm"))]
+mod tests {
+    use serde::Deserialize;
+    use wasm_bindgen::JsValue;
+    use wasm_bindgen_test::*;
+
+    use crate::{BaseTestWrapper, Vanilla};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Saved {
+        id: u32,
+    }
+
+    fn dispatch_custom(target: &web_sys::Element, name: &str, detail: &JsValue, bubbles: bool) {
+        let init = web_sys::CustomEventInit::new();
+        init.set_bubbles(bubbles);
+        init.set_detail(detail);
+        let event = web_sys::CustomEvent::new_with_event_init_dict(name, &init).unwrap();
+        target.dispatch_event(&event).unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    async fn spies_events() {
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html(
+            r#"<div id="editor"><button>save</button><span>status</span></div>"#,
+        );
+        let spy = wrapper
+            .query("#editor")
+            .assert_exists()
+            .spy_events(&["click", "editor:save"]);
+
+        wrapper
+            .query_as_button("button")
+            .assert_exists()
+            .click()
+            .await;
+        // doesn't bubble, but is still seen while capturing
+        let span = wrapper.query("span").assert_exists();
+        let detail = js_sys::JSON::parse(r#"{ "id": 3 }"#).unwrap();
+        dispatch_custom(&span, "editor:save", &detail, false);
+
+        spy.assert_fired_times("click", 1)
+            .assert_fired_times("editor:save", 1);
+        assert_eq!(
+            spy.calls()
+                .iter()
+                .map(|call| call.name())
+                .collect::<Vec<_>>(),
+            ["click", "editor:save"]
+        );
+
+        let saved = spy.last("editor:save").unwrap();
+        assert_eq!(saved.target().unwrap().tag_name(), "SPAN");
+        assert_eq!(saved.detail_json::<Saved>(), Saved { id: 3 });
+        assert_eq!(saved.property("bubbles"), JsValue::FALSE);
+
+        drop(spy);
+        let spy = wrapper
+            .query("#editor")
+            .assert_exists()
+            .spy_events(&["click"]);
+        spy.assert_not_fired("click");
+    }
+
+    #[wasm_bindgen_test]
+    #[should_panic = "`editor:save` wasn't fired"]
+    fn assert_fired_panics() {
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html("<div id=\"editor\"></div>");
+        let spy = wrapper
+            .query("#editor")
+            .assert_exists()
+            .spy_events(&["editor:save"]);
+
+        spy.assert_fired("editor:save");
+    }
+}
@@ -12,8 +12,7 @@ impl<Fw: Framework> TestWrapper<Single<web_sys::HtmlInputElement>, Fw> {
         target.dispatch_event(&crate::change_evt()).unwrap();
         target.dispatch_event(&crate::input_evt()).unwrap();
 
-        #[cfg(feature = "leptos")]
-        leptos::task::tick().await;
+        self.settle().await;
 
         self
     }
@@ -44,8 +43,22 @@ impl<Fw: Framework> TestWrapper<Single<web_sys::HtmlSelectElement>, Fw> {
         target.set_value(val);
         target.dispatch_event(&change_evt()).unwrap();
 
-        #[cfg(feature = "leptos")]
-        leptos::task::tick().await;
+        self.settle().await;
+
+        self
+    }
+}
+
+impl<Fw: Framework> TestWrapper<Single<web_sys::HtmlFormElement>, Fw> {
+    /// Submits the form as if the user pressed its submit button, so it's validated and the `submit`
+    /// handlers run
+    ///
+    /// Something has to prevent the default action (as an `<ActionForm>` does), otherwise the browser
+    /// navigates away from the test page
+    pub async fn submit(&self) -> &Self {
+        self.state.0.request_submit().unwrap();
+
+        self.settle().await;
 
         self
     }
@@ -57,8 +70,7 @@ impl<Fw: Framework, Elem: AsRef<HtmlElement>> TestWrapper<Single<Elem>, Fw> {
 
         target.click();
 
-        #[cfg(feature = "leptos")]
-        leptos::task::tick().await;
+        self.settle().await;
 
         self
     }
@@ -191,4 +203,57 @@ mod tests {
         // ASSERT
         assert!(clicked.load(Ordering::Acquire));
     }
+
+    #[wasm_bindgen_test]
+    async fn submit_submits() {
+        use wasm_bindgen::JsCast as _;
+
+        // ARRANGE
+        let submitted = Arc::new(Mutex::new(None));
+
+        let submitted_clone = Arc::clone(&submitted);
+        let wrapper = mount_test(|| {
+            view! {
+                <form on:submit=move |ev| {
+                    ev.prevent_default();
+                    let form = event_target::<web_sys::HtmlFormElement>(&ev);
+                    let name = form
+                        .elements()
+                        .named_item("name")
+                        .map(|input| input.unchecked_into::<web_sys::HtmlInputElement>().value());
+                    *submitted_clone.lock().unwrap() = name;
+                }>
+                    <input name="name" value="Ann" />
+                </form>
+            }
+        });
+
+        // ACT
+        wrapper.query_as_form("form").assert_exists().submit().await;
+
+        // ASSERT
+        assert_eq!(submitted.lock().unwrap().as_deref(), Some("Ann"));
+    }
+
+    #[wasm_bindgen_test]
+    async fn settles_with_configured_strategy() {
+        use crate::framework::SettleStrategy;
+
+        let wrapper = mount_test(|| {
+            let (count, set_count) = signal(0);
+            view! {
+                <span id="count">{count}</span>
+                <button on:click=move |_| set_count.update(|count| *count += 1)>increment</button>
+            }
+        })
+        .with_settle_strategy(SettleStrategy::Quiescent);
+
+        wrapper
+            .query_as_button("button")
+            .assert_exists()
+            .click()
+            .await;
+
+        wrapper.query("#count").assert_exists().assert_text_is("1");
+    }
 }
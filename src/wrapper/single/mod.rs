@@ -1,4 +1,5 @@
 pub mod asserts;
+pub mod events;
 pub mod interaction;
 pub mod traversal;
 
@@ -0,0 +1,104 @@
+use crate::{
+    framework::Framework,
+    snapshot::{Snapshot, SnapshotOptions, serialize, serialize_element},
+};
+
+use super::{TestWrapper, empty::Empty, single::Single};
+
+impl<Fw: Framework> TestWrapper<Empty, Fw> {
+    /// Takes a snapshot of everything mounted in the root
+    pub fn snapshot(&self, name: &str) -> Snapshot {
+        self.snapshot_with(name, &SnapshotOptions::default())
+    }
+
+    /// Takes a snapshot of everything mounted in the root with custom normalization options
+    pub fn snapshot_with(&self, name: &str, options: &SnapshotOptions) -> Snapshot {
+        Snapshot {
+            name: name.to_string(),
+            content: serialize(&self.root, options),
+        }
+    }
+}
+
+impl<E: Into<web_sys::Element> + Clone, Fw: Framework> TestWrapper<Single<E>, Fw> {
+    /// Takes a snapshot of this element and its subtree
+    pub fn snapshot(&self, name: &str) -> Snapshot {
+        self.snapshot_with(name, &SnapshotOptions::default())
+    }
+
+    /// Takes a snapshot of this element and its subtree with custom normalization options
+    pub fn snapshot_with(&self, name: &str, options: &SnapshotOptions) -> Snapshot {
+        Snapshot {
+            name: name.to_string(),
+            content: serialize_element(&self.state.0.clone().into(), options),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{BaseTestWrapper, Vanilla, assert_snapshot, snapshot::SnapshotOptions};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test(unsupported = test)]
+    #[cfg_attr(not(target_family = "wasm"), ignore)]
+    fn snapshots_root() {
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html(concat!(
+            "<main>",
+            r#"<p id="first" class="text">First</p>"#,
+            r#"<input type="text" disabled />"#,
+            "<ul><li>One</li><li>Two</li></ul>",
+            "</main>",
+        ));
+
+        assert_snapshot!(wrapper, @r#"
+            <main>
+              <p class="text" id="first">First</p>
+              <input disabled type="text">
+              <ul>
+                <li>One</li>
+                <li>Two</li>
+              </ul>
+            </main>
+        "#);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    #[cfg_attr(not(target_family = "wasm"), ignore)]
+    fn snapshots_single_with_redactions() {
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html(
+            r#"<div id="generated-123" data-testid="container"><span>Hello 123</span></div>"#,
+        );
+
+        let options = SnapshotOptions::default()
+            .redact_attr("id")
+            .remove_attrs(&["data-testid"])
+            .text_rule(|text| text.replace("123", "N"));
+
+        wrapper
+            .query("div")
+            .assert_exists()
+            .snapshot_with("redacted", &options)
+            .assert_matches(
+                r#"
+                <div id="[redacted]">
+                  <span>Hello N</span>
+                </div>
+                "#,
+            );
+    }
+
+    #[should_panic(expected = "snapshot `mismatch` does not match")]
+    #[wasm_bindgen_test(unsupported = test)]
+    #[cfg_attr(not(target_family = "wasm"), ignore)]
+    fn snapshot_mismatch_panics() {
+        let wrapper = BaseTestWrapper::<Vanilla>::from_html("<span>actual</span>");
+
+        wrapper
+            .snapshot("mismatch")
+            .assert_matches("<span>expected</span>");
+    }
+}